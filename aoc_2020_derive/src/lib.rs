@@ -0,0 +1,240 @@
+//! `#[derive(Parse)]` for the `parser_combinator::Parse` trait of the aoc_2020 crate,
+//! driven by a format annotation like `#[parse("{min}-{max} {required_char}: {password}")]`
+//!
+//! the generated impl names `crate::parser_combinator` directly, so it only works inside
+//! the aoc_2020 crate itself, other crates would need that path to be configurable
+
+use proc_macro::TokenStream;
+use proc_macro2::{Span, TokenStream as TokenStream2};
+use quote::{format_ident, quote};
+use syn::spanned::Spanned;
+use syn::{parse_macro_input, Attribute, Data, DeriveInput, Field, Fields, LitStr};
+
+#[proc_macro_derive(Parse, attributes(parse))]
+pub fn derive_parse(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[derive(Debug, PartialEq)]
+enum Segment {
+    Literal(String),
+    Field(String),
+}
+
+/// splits the format into literals and `{field}` placeholders, `{{` and `}}` are escapes
+fn parse_format(format: &LitStr) -> syn::Result<Vec<Segment>> {
+    let error = |message| syn::Error::new(format.span(), message);
+    let mut segments = Vec::new();
+    let mut literal = String::new();
+    let mut chars = format
+        .value()
+        .chars()
+        .collect::<Vec<_>>()
+        .into_iter()
+        .peekable();
+
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                literal.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                literal.push('}');
+            }
+            '{' => {
+                let name = chars.by_ref().take_while(|&c| c != '}').collect::<String>();
+                if name.is_empty() {
+                    return Err(error("empty field placeholder"));
+                }
+                if let Some(Segment::Field(_)) = segments.last() {
+                    if literal.is_empty() {
+                        return Err(error("fields must be separated by literals"));
+                    }
+                }
+                if !literal.is_empty() {
+                    segments.push(Segment::Literal(std::mem::take(&mut literal)));
+                }
+                segments.push(Segment::Field(name.trim().to_owned()));
+            }
+            '}' => return Err(error("unmatched `}`, use `}}` for a literal one")),
+            c => literal.push(c),
+        }
+    }
+
+    if !literal.is_empty() {
+        segments.push(Segment::Literal(literal));
+    }
+
+    Ok(segments)
+}
+
+fn format_attribute(attrs: &[Attribute], span: Span) -> syn::Result<LitStr> {
+    attrs
+        .iter()
+        .find(|attr| attr.path.is_ident("parse"))
+        .ok_or_else(|| syn::Error::new(span, "missing `#[parse(\"...\")]` format attribute"))?
+        .parse_args::<LitStr>()
+}
+
+/// statements parsing every segment in order, binding fields to `field_<name>` variables;
+/// a field followed by a literal only gets to see the trimmed input up to that literal
+fn parse_segments(
+    format: &LitStr,
+    segments: &[Segment],
+    fields: &Fields,
+) -> syn::Result<TokenStream2> {
+    let mut statements = Vec::new();
+    for (i, segment) in segments.iter().enumerate() {
+        match segment {
+            Segment::Literal(literal) => statements.push(quote! {
+                let (input, ()) = crate::parser_combinator::match_literal(#literal).parse(input)?;
+            }),
+            Segment::Field(name) => {
+                let ty = field_type(format, fields, name)?;
+                let variable = format_ident!("field_{}", name);
+                let statement = match segments.get(i + 1) {
+                    Some(Segment::Literal(next)) => quote! {
+                        let (input, field_input) = crate::parser_combinator::take_until(#next).parse(input)?;
+                        let (_, #variable) = crate::parser_combinator::all_consuming(
+                            <#ty as crate::parser_combinator::Parse>::parse_from,
+                        )
+                        .parse(field_input.trim())?;
+                    },
+                    _ => quote! {
+                        let (input, #variable) =
+                            <#ty as crate::parser_combinator::Parse>::parse_from(input.trim_start())?;
+                    },
+                };
+                statements.push(statement);
+            }
+        }
+    }
+
+    Ok(quote! { #(#statements)* })
+}
+
+/// type of the field a placeholder names, errors point at the format string
+fn field_type<'f>(format: &LitStr, fields: &'f Fields, name: &str) -> syn::Result<&'f syn::Type> {
+    let found = match fields {
+        Fields::Named(named) => named
+            .named
+            .iter()
+            .find(|field| field.ident.as_ref().is_some_and(|ident| ident == name)),
+        Fields::Unnamed(unnamed) => name
+            .parse::<usize>()
+            .ok()
+            .and_then(|index| unnamed.unnamed.iter().nth(index)),
+        Fields::Unit => None,
+    };
+
+    found
+        .map(|field| &field.ty)
+        .ok_or_else(|| syn::Error::new(format.span(), format!("no field named `{}`", name)))
+}
+
+/// `Self { a: field_a, .. }`, `Self(field_0, ..)` or `Self::Variant` style constructor,
+/// errors point at the field left out of the format
+fn construct(
+    path: TokenStream2,
+    fields: &Fields,
+    segments: &[Segment],
+) -> syn::Result<TokenStream2> {
+    let used = |field: &Field, name: String| {
+        if segments.contains(&Segment::Field(name.clone())) {
+            Ok(format_ident!("field_{}", name))
+        } else {
+            Err(syn::Error::new(
+                field.span(),
+                format!("field `{}` is missing from the format", name),
+            ))
+        }
+    };
+
+    Ok(match fields {
+        Fields::Named(named) => {
+            let names = named
+                .named
+                .iter()
+                .map(|field| field.ident.as_ref().unwrap());
+            let values = named
+                .named
+                .iter()
+                .zip(names.clone())
+                .map(|(field, name)| used(field, name.to_string()))
+                .collect::<syn::Result<Vec<_>>>()?;
+            quote! { #path { #(#names: #values),* } }
+        }
+        Fields::Unnamed(unnamed) => {
+            let values = unnamed
+                .unnamed
+                .iter()
+                .enumerate()
+                .map(|(index, field)| used(field, index.to_string()))
+                .collect::<syn::Result<Vec<_>>>()?;
+            quote! { #path(#(#values),*) }
+        }
+        Fields::Unit => quote! { #path },
+    })
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let body = match &input.data {
+        Data::Struct(data) => {
+            let format = format_attribute(&input.attrs, name.span())?;
+            let segments = parse_format(&format)?;
+            let statements = parse_segments(&format, &segments, &data.fields)?;
+            let value = construct(quote! { Self }, &data.fields, &segments)?;
+            quote! {
+                #statements
+                Ok((input, #value))
+            }
+        }
+        Data::Enum(data) => {
+            // variants are tried in declaration order, first one to parse wins
+            let attempts = data
+                .variants
+                .iter()
+                .map(|variant| {
+                    let format = format_attribute(&variant.attrs, variant.ident.span())?;
+                    let segments = parse_format(&format)?;
+                    let statements = parse_segments(&format, &segments, &variant.fields)?;
+                    let ident = &variant.ident;
+                    let value = construct(quote! { Self::#ident }, &variant.fields, &segments)?;
+                    Ok(quote! {
+                        let attempt = |input: &'a str| -> crate::parser_combinator::ParseResult<'a, Self> {
+                            #statements
+                            Ok((input, #value))
+                        };
+                        if let Ok(result) = attempt(input) {
+                            return Ok(result);
+                        }
+                    })
+                })
+                .collect::<syn::Result<Vec<_>>>()?;
+            quote! {
+                #(#attempts)*
+                Err(crate::parser_combinator::ParseError::Mismatch(input))
+            }
+        }
+        Data::Union(_) => {
+            return Err(syn::Error::new(name.span(), "unions can't derive `Parse`"));
+        }
+    };
+
+    Ok(quote! {
+        impl #impl_generics crate::parser_combinator::Parse for #name #ty_generics #where_clause {
+            fn parse_from<'a>(input: &'a str) -> crate::parser_combinator::ParseResult<'a, Self> {
+                use crate::parser_combinator::Parser;
+                #body
+            }
+        }
+    })
+}
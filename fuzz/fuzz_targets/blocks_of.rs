@@ -0,0 +1,7 @@
+#![no_main]
+use aoc_2020::parser_combinator::*;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let _ = blocks_of(sep_by(word_str(), whitespace_char()))(input);
+});
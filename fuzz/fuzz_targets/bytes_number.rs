@@ -0,0 +1,7 @@
+#![no_main]
+use aoc_2020::parser_combinator::*;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let _ = bytes::number().parse(input.as_bytes());
+});
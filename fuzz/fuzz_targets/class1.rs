@@ -0,0 +1,7 @@
+#![no_main]
+use aoc_2020::parser_combinator::*;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let _ = class1(('0'..='9', 'a'..='f')).parse(input);
+});
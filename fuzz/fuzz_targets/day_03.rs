@@ -0,0 +1,11 @@
+#![no_main]
+use aoc_2020::common::AocDay;
+use aoc_2020::day_03::Day;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let mut day = Day::default();
+    day.parse_input(input);
+    let _ = day.puzzle_00();
+    let _ = day.puzzle_01();
+});
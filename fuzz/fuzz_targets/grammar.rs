@@ -0,0 +1,9 @@
+#![no_main]
+use aoc_2020::parser_combinator::*;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    if let Ok(grammar) = Grammar::from_rules(input) {
+        let _ = grammar.matches(0, input);
+    }
+});
@@ -0,0 +1,7 @@
+#![no_main]
+use aoc_2020::parser_combinator::*;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let _ = grid_of(|c| c.to_digit(10).ok_or(()))(input);
+});
@@ -0,0 +1,12 @@
+#![no_main]
+use aoc_2020::parser_combinator::*;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    let _ = precedence(number())
+        .prefix("-", 3, |a: u32| a.wrapping_neg())
+        .infix("+", 1, Assoc::Left, u32::wrapping_add)
+        .infix("*", 2, Assoc::Right, u32::wrapping_mul)
+        .group("(", ")")
+        .parse(input);
+});
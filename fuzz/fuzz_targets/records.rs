@@ -0,0 +1,13 @@
+#![no_main]
+use aoc_2020::parser_combinator::*;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    for record in records(input.as_bytes(), |input: &str| {
+        left(number(), space_0()).parse(input)
+    })
+    .chunk_size(3)
+    {
+        let _ = record.map_err(|error| error.to_string());
+    }
+});
@@ -0,0 +1,9 @@
+#![no_main]
+use aoc_2020::parser_combinator::*;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    if let Ok((_, located)) = spanned(input, word_str()).parse(input) {
+        let _ = located.to_string();
+    }
+});
@@ -1,5 +1,4 @@
-use itertools::Itertools;
-
+pub use crate::grid::*;
 pub use crate::parser_combinator::Parser;
 pub use crate::parser_combinator::*;
 
@@ -9,16 +8,16 @@ pub trait AocDay {
     fn parse_input(&mut self, input: &str);
     fn puzzle_00(&self) -> Option<AocPuzzleAnswer>;
     fn puzzle_01(&self) -> Option<AocPuzzleAnswer>;
-}
 
-pub fn group_by_empty_lines(input: &str) -> Vec<Vec<String>> {
-    input
-        .lines()
-        .group_by(|line| line.trim().is_empty())
-        .into_iter()
-        .filter(|(key, _)| !key)
-        .map(|(_, lines)| lines.map(String::from).collect::<Vec<_>>())
-        .collect::<Vec<_>>()
+    /// problems with the input worth telling about, like lines that had to be skipped
+    fn diagnostics(&self) -> Vec<String> {
+        Vec::new()
+    }
+
+    /// runner option given on the command line as `day_NN.name=value`
+    fn set_option(&mut self, name: &str, _value: &str) -> Result<(), String> {
+        Err(format!("unknown option {:?}", name))
+    }
 }
 
 pub fn str_to_char_vec(s: &str) -> Vec<char> {
@@ -1,70 +1,581 @@
 use crate::common::*;
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::fmt::{self, Debug};
+use std::ops::Range;
+
+const TARGET: u64 = 2020;
 
 #[derive(Default)]
 pub struct Day {
-    numbers: Vec<u32>,
+    numbers: Vec<u64>,
+    parse_error: Option<String>,
 }
 
 impl AocDay for Day {
     fn parse_input(&mut self, input: &str) {
-        self.numbers = input
-            .split_whitespace()
-            .filter_map(|s| s.parse::<u32>().ok())
-            .collect();
+        // input is plain ASCII digits, so there is no point in decoding UTF-8
+        let numbers = all_consuming(right(
+            bytes::space_0(),
+            zero_or_more(left(bytes::unsigned(), bytes::space_0())),
+        ));
+        let input = input.as_bytes();
+        match numbers.parse(input) {
+            Ok((_, numbers)) => {
+                self.numbers = numbers;
+                self.parse_error = None;
+            }
+            Err(error) => {
+                // zero_or_more stops in front of a number that's too big,
+                // leaving it to all_consuming to report as a mismatch
+                let error = match bytes::unsigned::<u64>().parse(error.input()) {
+                    Err(overflow @ ParseError::Overflow(_)) => overflow,
+                    _ => error,
+                };
+                let offset = input.len() - error.input().len();
+                let reason = match error {
+                    ParseError::Mismatch(_) => "unexpected input",
+                    ParseError::Overflow(_) => "number out of range",
+                };
+                self.numbers.clear();
+                self.parse_error = Some(format!("byte {}: {}", offset, reason));
+            }
+        }
     }
 
+    /// a product too big for an answer is `None`, `diagnostics` tells why
     fn puzzle_00(&self) -> Option<AocPuzzleAnswer> {
-        let (a, b) = find_2_numbers_sum_2020(&self.numbers);
-        assert_eq!(a + b, 2020);
-        Some((a * b) as usize)
+        self.solve(2).unwrap_or(None)
     }
 
     fn puzzle_01(&self) -> Option<AocPuzzleAnswer> {
-        let (a, b, c) = find_3_numbers_sum_2020(&self.numbers);
-        assert_eq!(a + b + c, 2020);
-        Some((a * b * c) as usize)
+        self.solve(3).unwrap_or(None)
+    }
+
+    fn diagnostics(&self) -> Vec<String> {
+        let overflows = [(0, 2), (1, 3)].iter().filter_map(|&(puzzle, k)| {
+            let error = self.solve(k).err()?;
+            Some(format!(
+                "puzzle {:02}: product of {} entries: {}",
+                puzzle, k, error
+            ))
+        });
+        self.parse_error.iter().cloned().chain(overflows).collect()
     }
 }
 
-fn find_2_numbers_sum_2020(numbers: &[u32]) -> (u32, u32) {
-    for i in 0..numbers.len() {
-        for j in 0..numbers.len() {
-            if i == j {
-                continue;
-            }
+impl Day {
+    /// product of the first `k` entries adding up to 2020
+    pub fn solve(&self, k: usize) -> Result<Option<AocPuzzleAnswer>, Overflow> {
+        if self.parse_error.is_some() {
+            return Ok(None);
+        }
 
-            let a = numbers[i];
-            let b = numbers[j];
-            if a + b == 2020 {
-                return (a, b);
+        match find_k_sum(&self.numbers, k, TARGET) {
+            Some(witness) => {
+                let product = witness.product()?;
+                AocPuzzleAnswer::try_from(product)
+                    .map(Some)
+                    .map_err(|_| Overflow)
             }
+            None => Ok(None),
         }
     }
+}
+
+/// arithmetic result that doesn't fit the integer type it's computed in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Overflow;
+
+impl fmt::Display for Overflow {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "arithmetic overflow")
+    }
+}
+
+/// unsigned integers the solver works with, every sum and product it computes is checked
+pub trait Unsigned: Integer + Ord + Debug {
+    const ZERO: Self;
+    const ONE: Self;
 
-    panic!("No numbers add up to 2020!");
+    fn checked_add(self, rhs: Self) -> Option<Self>;
+    fn checked_sub(self, rhs: Self) -> Option<Self>;
+    fn checked_mul(self, rhs: Self) -> Option<Self>;
+    fn from_usize(n: usize) -> Option<Self>;
 }
 
-fn find_3_numbers_sum_2020(numbers: &[u32]) -> (u32, u32, u32) {
-    for i in 0..numbers.len() {
-        for j in 0..numbers.len() {
-            if i == j {
-                continue;
+macro_rules! impl_unsigned {
+    ($($t:ty)*) => {
+        $(
+            impl Unsigned for $t {
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                fn checked_add(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_add(self, rhs)
+                }
+
+                fn checked_sub(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_sub(self, rhs)
+                }
+
+                fn checked_mul(self, rhs: Self) -> Option<Self> {
+                    <$t>::checked_mul(self, rhs)
+                }
+
+                fn from_usize(n: usize) -> Option<Self> {
+                    <$t>::try_from(n).ok()
+                }
             }
+        )*
+    };
+}
+
+impl_unsigned!(u8 u16 u32 u64 u128 usize);
 
-            for k in 0..numbers.len() {
-                if k == i || k == j {
-                    continue;
+/// numbers adding up to a target, `indices` into the searched slice in increasing order
+/// and `values` found at them
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Witness<T> {
+    pub indices: Vec<usize>,
+    pub values: Vec<T>,
+}
+
+impl<T: Unsigned> Witness<T> {
+    pub fn product(&self) -> Result<T, Overflow> {
+        self.values
+            .iter()
+            .try_fold(T::ONE, |product, &x| product.checked_mul(x))
+            .ok_or(Overflow)
+    }
+}
+
+/// any `k` numbers at distinct positions adding up to `target`
+pub fn find_k_sum<T: Unsigned>(numbers: &[T], k: usize, target: T) -> Option<Witness<T>> {
+    KSum::new(numbers, Picks::Distinct).first(k, target)
+}
+
+/// every set of `k` distinct positions holding numbers that add up to `target`
+pub fn find_all_k_sums<T: Unsigned>(numbers: &[T], k: usize, target: T) -> Vec<Witness<T>> {
+    KSum::new(numbers, Picks::Distinct).all(k, target)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Picks {
+    /// every index at most once
+    Distinct,
+    /// indices can repeat, `[1010]` has `1010 + 1010` adding up to 2020
+    WithReplacement,
+}
+
+/// numbers sorted along with their original indices, searched by fixing the smallest
+/// pick and recursing down to a two-pointer sweep, O(n log n + n^(k - 1));
+/// combinations are sets of indices, so equal numbers at different positions make for
+/// different ones; sums too big for `T` can't be equal to the target, so they don't
+/// need to be computed
+pub struct KSum<T> {
+    sorted: Vec<(T, usize)>,
+    picks: Picks,
+}
+
+/// last picks of a combination, as positions in the sorted numbers
+enum Tail {
+    Empty,
+    One(Range<usize>),
+    /// any two of the range, both holding the same number
+    Pair(Range<usize>),
+    /// one of each range
+    Cross(Range<usize>, Range<usize>),
+}
+
+type Visitor<'v> = dyn FnMut(&[usize], Tail) -> bool + 'v;
+
+impl<T: Unsigned> KSum<T> {
+    pub fn new(numbers: &[T], picks: Picks) -> Self {
+        let mut sorted = numbers
+            .iter()
+            .enumerate()
+            .map(|(i, &x)| (x, i))
+            .collect::<Vec<_>>();
+        sorted.sort_unstable();
+        Self { sorted, picks }
+    }
+
+    pub fn first(&self, k: usize, target: T) -> Option<Witness<T>> {
+        let mut witness = None;
+        self.for_each(k, target, |found| {
+            witness = Some(found);
+            true
+        });
+        witness
+    }
+
+    /// every combination, ordered by indices
+    pub fn all(&self, k: usize, target: T) -> Vec<Witness<T>> {
+        let mut witnesses = Vec::new();
+        self.for_each(k, target, |found| {
+            witnesses.push(found);
+            false
+        });
+        witnesses.sort_by(|a, b| a.indices.cmp(&b.indices));
+        witnesses
+    }
+
+    /// calls `f` with every combination of `k` numbers adding up to `target`, in no
+    /// particular order, until it returns `true`
+    pub fn for_each<F: FnMut(Witness<T>) -> bool>(&self, k: usize, target: T, mut f: F) {
+        self.search(0, k, target, &mut Vec::new(), &mut |picked, tail| {
+            let mut visit = |last: &[usize]| f(self.witness(picked.iter().chain(last)));
+            match tail {
+                Tail::Empty => visit(&[]),
+                Tail::One(mut range) => range.any(|i| visit(&[i])),
+                Tail::Pair(range) => self.pairs(range).any(|(i, j)| visit(&[i, j])),
+                Tail::Cross(a, b) => a
+                    .flat_map(|i| b.clone().map(move |j| (i, j)))
+                    .any(|(i, j)| visit(&[i, j])),
+            }
+        });
+    }
+
+    /// number of combinations `for_each` would go through, without building any of them
+    pub fn count(&self, k: usize, target: T) -> u64 {
+        let mut count = 0;
+        self.search(0, k, target, &mut Vec::new(), &mut |_, tail| {
+            count += match tail {
+                Tail::Empty => 1,
+                Tail::One(range) => range.len() as u64,
+                Tail::Pair(range) => {
+                    let n = range.len() as u64;
+                    match self.picks {
+                        Picks::Distinct => n * (n - 1) / 2,
+                        Picks::WithReplacement => n * (n + 1) / 2,
+                    }
                 }
+                Tail::Cross(a, b) => a.len() as u64 * b.len() as u64,
+            };
+            false
+        });
+        count
+    }
+
+    fn pairs(&self, range: Range<usize>) -> impl Iterator<Item = (usize, usize)> {
+        let skip = match self.picks {
+            Picks::Distinct => 1,
+            Picks::WithReplacement => 0,
+        };
+        let end = range.end;
+        range.flat_map(move |i| (i + skip..end).map(move |j| (i, j)))
+    }
+
+    fn witness<'p>(&self, positions: impl Iterator<Item = &'p usize>) -> Witness<T> {
+        let mut picks = positions
+            .map(|&i| (self.sorted[i].1, self.sorted[i].0))
+            .collect::<Vec<_>>();
+        picks.sort_unstable();
+        Witness {
+            indices: picks.iter().map(|&(i, _)| i).collect(),
+            values: picks.iter().map(|&(_, x)| x).collect(),
+        }
+    }
+
+    /// sorted positions from `start` on are still available for picking, stops as soon
+    /// as `visit` returns `true` and passes that back up
+    fn search(
+        &self,
+        start: usize,
+        k: usize,
+        target: T,
+        picked: &mut Vec<usize>,
+        visit: &mut Visitor,
+    ) -> bool {
+        let len = self.sorted.len();
+        match k {
+            0 => target == T::ZERO && visit(picked, Tail::Empty),
+            1 => {
+                let first = start + self.sorted[start..].partition_point(|&(x, _)| x < target);
+                let last = first + self.sorted[first..].partition_point(|&(x, _)| x == target);
+                first < last && visit(picked, Tail::One(first..last))
+            }
+            2 => self.two_pointer(start, target, picked, visit),
+            _ => {
+                for i in start..len {
+                    let x = self.sorted[i].0;
+                    // the rest are at least as big as `x`
+                    let at_least = T::from_usize(k).and_then(|k| x.checked_mul(k));
+                    let rest = match (at_least, target.checked_sub(x)) {
+                        (Some(at_least), Some(rest)) if at_least <= target => rest,
+                        _ => break,
+                    };
 
-                let a = numbers[i];
-                let b = numbers[j];
-                let c = numbers[k];
-                if a + b + c == 2020 {
-                    return (a, b, c);
+                    let next = match self.picks {
+                        Picks::Distinct => i + 1,
+                        Picks::WithReplacement => i,
+                    };
+                    picked.push(i);
+                    let stop = self.search(next, k - 1, rest, picked, visit);
+                    picked.pop();
+                    if stop {
+                        return true;
+                    }
                 }
+                false
             }
         }
     }
 
-    panic!("No three numbers add up to 2020!");
+    fn two_pointer(&self, start: usize, target: T, picked: &[usize], visit: &mut Visitor) -> bool {
+        if start >= self.sorted.len() {
+            return false;
+        }
+
+        // with replacement both pointers may land on the same number
+        let overlap = match self.picks {
+            Picks::Distinct => 0,
+            Picks::WithReplacement => 1,
+        };
+        let (mut low, mut high) = (start, self.sorted.len() - 1);
+        while low < high + overlap {
+            let (a, b) = (self.sorted[low].0, self.sorted[high].0);
+            // a sum that overflows is too big as well
+            let order = a
+                .checked_add(b)
+                .map_or(Ordering::Greater, |sum| sum.cmp(&target));
+            match order {
+                Ordering::Less => low += 1,
+                Ordering::Greater if high == 0 => break,
+                Ordering::Greater => high -= 1,
+                // everything in between is equal too
+                Ordering::Equal if a == b => return visit(picked, Tail::Pair(low..high + 1)),
+                Ordering::Equal => {
+                    let low_end = low + self.sorted[low..high].partition_point(|&(x, _)| x == a);
+                    let high_start =
+                        low_end + self.sorted[low_end..high].partition_point(|&(x, _)| x < b);
+                    if visit(picked, Tail::Cross(low..low_end, high_start..high + 1)) {
+                        return true;
+                    }
+                    low = low_end;
+                    high = high_start - 1;
+                }
+            }
+        }
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use itertools::Itertools;
+    use proptest::prelude::*;
+
+    const EXAMPLE: &[u32] = &[1721, 979, 366, 299, 675, 1456];
+
+    #[test]
+    fn example_sums() {
+        assert_eq!(
+            Some(Witness {
+                indices: vec![0, 3],
+                values: vec![1721, 299]
+            }),
+            find_k_sum(EXAMPLE, 2, 2020)
+        );
+        assert_eq!(
+            Some(241861950),
+            find_k_sum(EXAMPLE, 3, 2020).map(|witness| witness.product().unwrap())
+        );
+        assert_eq!(None, find_k_sum(EXAMPLE, 2, 2021));
+        assert_eq!(None, find_k_sum::<u32>(&[], 2, 2020));
+        assert_eq!(None, find_k_sum(EXAMPLE, 7, 2020));
+
+        let mut day = Day::default();
+        day.parse_input("1721\n979\n366\n299\n675\n1456\n");
+        assert_eq!(Some(514579), day.puzzle_00());
+        assert_eq!(Some(241861950), day.puzzle_01());
+        day.parse_input("1\n2\n");
+        assert_eq!(None, day.puzzle_00());
+        assert_eq!(None, day.puzzle_01());
+    }
+
+    #[test]
+    fn duplicates_are_distinct_witnesses() {
+        let indices = |witnesses: Vec<Witness<u32>>| {
+            witnesses
+                .into_iter()
+                .map(|witness| witness.indices)
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(None, find_k_sum(&[1010u32], 2, 2020));
+        assert_eq!(
+            vec![vec![0, 1], vec![0, 2], vec![1, 2]],
+            indices(find_all_k_sums(&[1010u32, 1010, 1010], 2, 2020))
+        );
+        assert_eq!(
+            vec![vec![0, 1], vec![0, 3], vec![1, 2], vec![2, 3]],
+            indices(find_all_k_sums(&[1u32, 3, 1, 3], 2, 4))
+        );
+        assert_eq!(
+            vec![vec![0, 1, 3], vec![0, 2, 3], vec![1, 2, 3]],
+            indices(find_all_k_sums(&[2u32, 2, 2, 0], 3, 4))
+        );
+        assert_eq!(
+            vec![Vec::<usize>::new()],
+            indices(find_all_k_sums(&[5u32], 0, 0))
+        );
+    }
+
+    #[test]
+    fn with_replacement() {
+        let search = KSum::new(&[1010u32, 5, 2015], Picks::WithReplacement);
+        assert_eq!(
+            vec![vec![0, 0], vec![1, 2]],
+            search
+                .all(2, 2020)
+                .into_iter()
+                .map(|witness| witness.indices)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(2, search.count(2, 2020));
+        assert_eq!(1, search.count(3, 15));
+        assert_eq!(
+            0,
+            KSum::new(&[1010u32, 5, 2015], Picks::Distinct).count(3, 15)
+        );
+        assert_eq!(1, KSum::<u32>::new(&[], Picks::WithReplacement).count(0, 0));
+    }
+
+    #[test]
+    fn first_stops_at_the_first_combination() {
+        // hundreds of millions of pairs, none of them should be built after the first
+        let numbers = vec![1010u32; 20_000];
+        let mut visited = 0;
+        KSum::new(&numbers, Picks::Distinct).for_each(2, 2020, |_| {
+            visited += 1;
+            true
+        });
+        assert_eq!(1, visited);
+        assert!(find_k_sum(&numbers, 3, 3030).is_some());
+    }
+
+    #[test]
+    fn adversarial_large_numbers() {
+        // every pair but one overflows u64 when added up
+        let max = u64::MAX;
+        let numbers = [max, max - 1, 1, max / 2 + 1, max / 2 + 1];
+        assert_eq!(
+            vec![vec![1, 2]],
+            find_all_k_sums(&numbers, 2, max)
+                .into_iter()
+                .map(|witness| witness.indices)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(0, KSum::new(&numbers, Picks::Distinct).count(3, max));
+        assert_eq!(None, find_k_sum(&numbers, 4, max));
+        assert_eq!(
+            2,
+            KSum::new(&numbers, Picks::Distinct).count(2, max / 2 + 2)
+        );
+        assert_eq!(0, KSum::new(&numbers, Picks::Distinct).count(2, 2));
+        assert_eq!(1, KSum::new(&numbers, Picks::WithReplacement).count(2, 2));
+
+        let witness = find_k_sum(&[1u64 << 32, 1 << 32], 2, 1 << 33).unwrap();
+        assert_eq!(Err(Overflow), witness.product());
+        let witness = find_k_sum(&[200u8, 100, 55], 2, 255).unwrap();
+        assert_eq!(vec![200, 55], witness.values);
+        assert_eq!(Err(Overflow), witness.product());
+        assert_eq!(0, KSum::new(&[200u8; 64], Picks::Distinct).count(3, 200));
+    }
+
+    #[test]
+    fn day_reports_overflow() {
+        let mut day = Day::default();
+        day.parse_input("1 99999999999999999999999");
+        assert_eq!(
+            Some("byte 2: number out of range".to_owned()),
+            day.parse_error
+        );
+        assert_eq!(None, day.puzzle_00());
+
+        day.parse_input("1 x");
+        assert_eq!(Some("byte 2: unexpected input".to_owned()), day.parse_error);
+        assert_eq!(vec!["byte 2: unexpected input"], day.diagnostics());
+
+        day.parse_input("0 2020 1000 1020");
+        assert_eq!(None, day.parse_error);
+        assert_eq!(Ok(Some(0)), day.solve(2));
+        assert_eq!(Ok(Some(0)), day.solve(3));
+        assert_eq!(
+            Ok(Some(0)),
+            day.solve(1).map(|answer| answer.map(|x| x - 2020))
+        );
+    }
+
+    #[test]
+    fn nothing_to_diagnose() {
+        // entries adding up to 2020 can't multiply to anything that doesn't fit,
+        // overflowing products are covered by `overflow_matches_brute_force`
+        let mut day = Day::default();
+        day.parse_input("1010 1010 0 673 673 674");
+        assert_eq!(Some(1010 * 1010), day.puzzle_00());
+        assert_eq!(Some(0), day.puzzle_01());
+        assert!(day.diagnostics().is_empty());
+    }
+
+    fn brute_force(numbers: &[u8], k: usize, target: u8, picks: Picks) -> Vec<Vec<usize>> {
+        let indices = 0..numbers.len();
+        let combinations = match picks {
+            Picks::Distinct => indices.combinations(k).collect::<Vec<_>>(),
+            Picks::WithReplacement => indices.combinations_with_replacement(k).collect(),
+        };
+        combinations
+            .into_iter()
+            .filter(|indices| {
+                indices.iter().map(|&i| numbers[i] as u64).sum::<u64>() == target as u64
+            })
+            .collect()
+    }
+
+    proptest! {
+        #[test]
+        fn matches_brute_force(
+            numbers in prop::collection::vec(0u8..20, 0..12),
+            k in 0usize..5,
+            target in 0u8..50,
+            with_replacement in any::<bool>(),
+        ) {
+            let picks = if with_replacement { Picks::WithReplacement } else { Picks::Distinct };
+            let search = KSum::new(&numbers, picks);
+            let expected = brute_force(&numbers, k, target, picks);
+            let witnesses = search.all(k, target);
+            prop_assert_eq!(
+                &expected,
+                &witnesses.iter().map(|witness| witness.indices.clone()).collect::<Vec<_>>()
+            );
+            for witness in &witnesses {
+                let values = witness.indices.iter().map(|&i| numbers[i]).collect::<Vec<_>>();
+                prop_assert_eq!(&values, &witness.values);
+            }
+            prop_assert_eq!(expected.len() as u64, search.count(k, target));
+            prop_assert_eq!(expected.is_empty(), search.first(k, target).is_none());
+        }
+
+        /// numbers big enough for sums and products to overflow `u8`
+        #[test]
+        fn overflow_matches_brute_force(
+            numbers in prop::collection::vec(any::<u8>(), 0..10),
+            k in 0usize..5,
+            target in any::<u8>(),
+        ) {
+            let expected = brute_force(&numbers, k, target, Picks::Distinct);
+            let witnesses = find_all_k_sums(&numbers, k, target);
+            prop_assert_eq!(
+                &expected,
+                &witnesses.iter().map(|witness| witness.indices.clone()).collect::<Vec<_>>()
+            );
+            for witness in &witnesses {
+                let product = witness.values.iter().map(|&x| x as u64).product::<u64>();
+                let expected = u8::try_from(product).map_err(|_| Overflow);
+                prop_assert_eq!(expected, witness.product());
+            }
+        }
+    }
 }
@@ -1,92 +1,445 @@
 use crate::common::*;
-use std::convert::{TryFrom, TryInto};
+use unicode_segmentation::UnicodeSegmentation;
 
-#[derive(Default)]
 pub struct Day {
     entries: Vec<Entry>,
+    /// lines that aren't entries, they don't count towards any policy
+    rejected_lines: Vec<String>,
+    policies: PolicyRegistry,
+    /// names of the policies puzzle_00 and puzzle_01 validate with
+    selected: [String; 2],
+}
+
+impl Default for Day {
+    fn default() -> Self {
+        Self {
+            entries: Vec::new(),
+            rejected_lines: Vec::new(),
+            policies: PolicyRegistry::default(),
+            selected: [
+                CountPolicy.name().into(),
+                PositionPolicy::default().name().into(),
+            ],
+        }
+    }
 }
 
 impl AocDay for Day {
     fn parse_input(&mut self, input: &str) {
-        self.entries = input
-            .lines()
-            .filter_map(|line| line.try_into().ok())
-            .collect();
+        self.entries.clear();
+        self.rejected_lines.clear();
+        let entry = all_consuming(left(parsed(), space_0()));
+        for (i, line) in input.lines().enumerate() {
+            if line.trim().is_empty() {
+                continue;
+            }
+            match entry.parse(line) {
+                Ok((_, entry)) => self.entries.push(entry),
+                Err(error) => self
+                    .rejected_lines
+                    .push(LineError { line: i + 1, error }.to_string()),
+            }
+        }
     }
 
     fn puzzle_00(&self) -> Option<AocPuzzleAnswer> {
-        Some(count_valid_passwords_v1(&self.entries))
+        self.count_valid(&self.selected[0])
     }
 
     fn puzzle_01(&self) -> Option<AocPuzzleAnswer> {
-        Some(count_valid_passwords_v2(&self.entries))
+        self.count_valid(&self.selected[1])
+    }
+
+    fn diagnostics(&self) -> Vec<String> {
+        self.rejected_lines.clone()
+    }
+
+    /// `policy_00` and `policy_01` pick a registered policy by name for each puzzle
+    fn set_option(&mut self, name: &str, value: &str) -> Result<(), String> {
+        let puzzle = match name {
+            "policy_00" => 0,
+            "policy_01" => 1,
+            _ => return Err(format!("unknown option {:?}", name)),
+        };
+        if self.policies.get(value).is_none() {
+            return Err(format!(
+                "unknown policy {:?}, expected one of {}",
+                value,
+                self.policies.names().join(", ")
+            ));
+        }
+        self.selected[puzzle] = value.to_owned();
+        Ok(())
+    }
+}
+
+impl Day {
+    /// `line N: ...` for every line that couldn't be parsed as an entry
+    pub fn rejected_lines(&self) -> &[String] {
+        &self.rejected_lines
+    }
+
+    pub fn register_policy(&mut self, policy: Box<dyn PasswordPolicy>) {
+        self.policies.register(policy);
+    }
+
+    /// entries the policy registered under `name` rejects
+    pub fn rejected(&self, name: &str) -> Option<Vec<Rejection<'_>>> {
+        let policy = self.policies.get(name)?;
+        Some(rejected(&self.entries, policy))
+    }
+
+    fn count_valid(&self, name: &str) -> Option<AocPuzzleAnswer> {
+        let policy = self.policies.get(name)?;
+        Some(
+            self.entries
+                .iter()
+                .filter(|entry| policy.check(entry).is_ok())
+                .count(),
+        )
     }
 }
 
-struct Entry {
-    min: usize,
-    max: usize,
-    required_char: char,
-    password: String,
+#[derive(Debug, Clone, PartialEq, Eq, Parse)]
+#[parse("{min}-{max} {required_char}:{password}")]
+pub struct Entry {
+    pub min: usize,
+    pub max: usize,
+    pub required_char: char,
+    pub password: String,
 }
 
-impl TryFrom<&str> for Entry {
-    type Error = anyhow::Error;
+pub trait PasswordPolicy {
+    /// what the policy is registered and selected by
+    fn name(&self) -> &'static str;
 
-    fn try_from(value: &str) -> Result<Self, Self::Error> {
-        let (head, tail) = value
-            .split_once('-')
-            .ok_or_else(|| Self::Error::msg("min"))?;
-        let min = head.parse::<usize>()?;
+    /// why `entry` isn't valid, if it isn't
+    fn check(&self, entry: &Entry) -> Result<(), String>;
+}
 
-        let (head, tail) = tail
-            .split_once(' ')
-            .ok_or_else(|| Self::Error::msg("max"))?;
-        let max = head.parse::<usize>()?;
+/// the sled rental one, the character occurs between `min` and `max` times
+pub struct CountPolicy;
 
-        let (head, tail) = tail
-            .split_once(':')
-            .ok_or_else(|| Self::Error::msg("required_char split"))?;
-        let required_char = head
-            .trim()
+impl PasswordPolicy for CountPolicy {
+    fn name(&self) -> &'static str {
+        "count"
+    }
+
+    fn check(&self, entry: &Entry) -> Result<(), String> {
+        let count = entry
+            .password
             .chars()
-            .nth(0)
-            .ok_or_else(|| Self::Error::msg("required_char"))?;
-        let password = tail.trim().to_owned();
-
-        Ok(Self {
-            min,
-            max,
-            required_char,
-            password,
+            .filter(|&c| c == entry.required_char)
+            .count();
+        if (entry.min..=entry.max).contains(&count) {
+            Ok(())
+        } else {
+            Err(format!(
+                "{:?} occurs {} times, expected {} to {}",
+                entry.required_char, count, entry.min, entry.max
+            ))
+        }
+    }
+}
+
+/// what the positions of `PositionPolicy` count
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PositionUnit {
+    Chars,
+    /// user-perceived characters, so `"e\u{301}"` is a single position
+    Graphemes,
+}
+
+/// the toboggan one, the character is at exactly one of the 1-based positions `min` and `max`,
+/// positions outside of the password fail validation
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PositionPolicy {
+    pub unit: PositionUnit,
+}
+
+impl Default for PositionPolicy {
+    fn default() -> Self {
+        Self {
+            unit: PositionUnit::Chars,
+        }
+    }
+}
+
+impl PositionPolicy {
+    /// whether the `unit` at 1-based `position` is the required character
+    fn matches_at(&self, entry: &Entry, position: usize) -> Result<bool, String> {
+        let required = entry.required_char.to_string();
+        let (found, len) = match self.unit {
+            PositionUnit::Chars => (
+                position
+                    .checked_sub(1)
+                    .and_then(|i| entry.password.chars().nth(i))
+                    .map(|c| c == entry.required_char),
+                entry.password.chars().count(),
+            ),
+            PositionUnit::Graphemes => (
+                position
+                    .checked_sub(1)
+                    .and_then(|i| entry.password.graphemes(true).nth(i))
+                    .map(|grapheme| grapheme == required),
+                entry.password.graphemes(true).count(),
+            ),
+        };
+
+        found.ok_or_else(|| {
+            format!(
+                "position {} is outside of the password, which has {} {}",
+                position,
+                len,
+                match self.unit {
+                    PositionUnit::Chars => "chars",
+                    PositionUnit::Graphemes => "graphemes",
+                }
+            )
         })
     }
 }
 
-fn count_valid_passwords_v1(entries: &[Entry]) -> usize {
+impl PasswordPolicy for PositionPolicy {
+    fn name(&self) -> &'static str {
+        match self.unit {
+            PositionUnit::Chars => "position",
+            PositionUnit::Graphemes => "position_graphemes",
+        }
+    }
+
+    fn check(&self, entry: &Entry) -> Result<(), String> {
+        let first_match = self.matches_at(entry, entry.min)?;
+        let second_match = self.matches_at(entry, entry.max)?;
+
+        match (first_match, second_match) {
+            (true, false) | (false, true) => Ok(()),
+            (true, true) => Err(format!(
+                "{:?} at both positions {} and {}",
+                entry.required_char, entry.min, entry.max
+            )),
+            (false, false) => Err(format!(
+                "{:?} at neither position {} nor {}",
+                entry.required_char, entry.min, entry.max
+            )),
+        }
+    }
+}
+
+/// policies by name, starting out with the built-in ones
+pub struct PolicyRegistry {
+    policies: Vec<Box<dyn PasswordPolicy>>,
+}
+
+impl Default for PolicyRegistry {
+    fn default() -> Self {
+        Self {
+            policies: vec![
+                Box::new(CountPolicy),
+                Box::new(PositionPolicy {
+                    unit: PositionUnit::Chars,
+                }),
+                Box::new(PositionPolicy {
+                    unit: PositionUnit::Graphemes,
+                }),
+            ],
+        }
+    }
+}
+
+impl PolicyRegistry {
+    /// replaces a policy already registered under the same name
+    pub fn register(&mut self, policy: Box<dyn PasswordPolicy>) {
+        self.policies.retain(|known| known.name() != policy.name());
+        self.policies.push(policy);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&dyn PasswordPolicy> {
+        self.policies
+            .iter()
+            .find(|policy| policy.name() == name)
+            .map(|policy| policy.as_ref())
+    }
+
+    pub fn names(&self) -> Vec<&'static str> {
+        self.policies.iter().map(|policy| policy.name()).collect()
+    }
+}
+
+/// entry that failed validation, `index` into the validated entries
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Rejection<'e> {
+    pub index: usize,
+    pub entry: &'e Entry,
+    pub reason: String,
+}
+
+pub fn rejected<'e>(entries: &'e [Entry], policy: &dyn PasswordPolicy) -> Vec<Rejection<'e>> {
     entries
         .iter()
-        .filter(|&entry| {
-            let count = entry
-                .password
-                .chars()
-                .filter(|&c| c == entry.required_char)
-                .count();
-            (entry.min..=entry.max).contains(&count)
+        .enumerate()
+        .filter_map(|(index, entry)| {
+            policy.check(entry).err().map(|reason| Rejection {
+                index,
+                entry,
+                reason,
+            })
         })
-        .count()
+        .collect()
 }
 
-fn count_valid_passwords_v2(entries: &[Entry]) -> usize {
-    entries
-        .iter()
-        .filter(|&entry| {
-            let first_match =
-                entry.password.chars().nth(entry.min - 1).unwrap() == entry.required_char;
-            let second_match =
-                entry.password.chars().nth(entry.max - 1).unwrap() == entry.required_char;
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-            (first_match || second_match) && !(first_match && second_match)
-        })
-        .count()
+    const EXAMPLE: &str = "1-3 a: abcde\n1-3 b: cdefg\n2-9 c: ccccccccc\n";
+
+    /// valid when the password doesn't contain the character at all
+    struct AbsentPolicy;
+
+    impl PasswordPolicy for AbsentPolicy {
+        fn name(&self) -> &'static str {
+            "absent"
+        }
+
+        fn check(&self, entry: &Entry) -> Result<(), String> {
+            match entry.password.find(entry.required_char) {
+                Some(i) => Err(format!("{:?} at byte {}", entry.required_char, i)),
+                None => Ok(()),
+            }
+        }
+    }
+
+    #[test]
+    fn builtin_policies() {
+        let mut day = Day::default();
+        day.parse_input(EXAMPLE);
+        assert_eq!(Some(2), day.puzzle_00());
+        assert_eq!(Some(1), day.puzzle_01());
+
+        let reasons = |rejections: Vec<Rejection>| {
+            rejections
+                .into_iter()
+                .map(|rejection| (rejection.index, rejection.reason))
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            Some(vec![(1, "'b' occurs 0 times, expected 1 to 3".to_owned())]),
+            day.rejected("count").map(reasons)
+        );
+        assert_eq!(
+            Some(vec![
+                (1, "'b' at neither position 1 nor 3".to_owned()),
+                (2, "'c' at both positions 2 and 9".to_owned())
+            ]),
+            day.rejected("position").map(reasons)
+        );
+        assert_eq!(None, day.rejected("nonexistent"));
+    }
+
+    #[test]
+    fn policies_selected_by_name() {
+        let mut day = Day::default();
+        day.parse_input(EXAMPLE);
+        day.register_policy(Box::new(AbsentPolicy));
+        assert_eq!(Ok(()), day.set_option("policy_01", "absent"));
+        assert_eq!(Some(1), day.puzzle_01());
+        assert_eq!(Ok(()), day.set_option("policy_00", "position"));
+        assert_eq!(Some(1), day.puzzle_00());
+
+        assert_eq!(
+            Err("unknown policy \"wat\", expected one of count, position, position_graphemes, absent".to_owned()),
+            day.set_option("policy_00", "wat")
+        );
+        assert_eq!(
+            Err("unknown option \"policy\"".to_owned()),
+            day.set_option("policy", "count")
+        );
+    }
+
+    fn check(policy: PositionPolicy, line: &str) -> Result<(), String> {
+        let (_, entry) = all_consuming(parsed::<Entry>()).parse(line).unwrap();
+        policy.check(&entry)
+    }
+
+    #[test]
+    fn positions_out_of_range() {
+        let chars = PositionPolicy::default();
+        assert_eq!(
+            Err("position 0 is outside of the password, which has 5 chars".to_owned()),
+            check(chars, "0-3 a: abcde")
+        );
+        assert_eq!(
+            Err("position 6 is outside of the password, which has 5 chars".to_owned()),
+            check(chars, "1-6 a: abcde")
+        );
+        assert_eq!(Ok(()), check(chars, "1-5 a: abcde"));
+
+        let mut day = Day::default();
+        day.parse_input("0-3 a: abcde\n1-3 a: abcde\n2-99 b: abcde\n");
+        assert_eq!(Some(1), day.puzzle_01());
+        assert_eq!(
+            vec![0, 2],
+            day.rejected("position")
+                .unwrap()
+                .into_iter()
+                .map(|rejection| rejection.index)
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn positions_in_graphemes() {
+        let chars = PositionPolicy::default();
+        let graphemes = PositionPolicy {
+            unit: PositionUnit::Graphemes,
+        };
+
+        // multi-byte chars count as one position either way
+        assert_eq!(Ok(()), check(chars, "2-3 é: aéb"));
+        assert_eq!(Ok(()), check(graphemes, "2-3 é: aéb"));
+
+        // 'e' followed by a combining acute accent is two chars but one grapheme
+        let line = "3-4 c: ae\u{301}bc";
+        assert_eq!(Ok(()), check(graphemes, line));
+        assert_eq!(
+            Err("'c' at neither position 3 nor 4".to_owned()),
+            check(chars, line)
+        );
+        assert_eq!(
+            Err("'e' at neither position 2 nor 3".to_owned()),
+            check(graphemes, "2-3 e: ae\u{301}bc")
+        );
+        assert_eq!(
+            Err("position 5 is outside of the password, which has 4 graphemes".to_owned()),
+            check(graphemes, "1-5 a: ae\u{301}bc")
+        );
+    }
+
+    #[test]
+    fn malformed_lines_are_reported() {
+        let mut day = Day::default();
+        day.parse_input("1-3 a: abcde\n1-3 b cdefg\n\n2-x c: ccccccccc\n2-9 c: ccccccccc");
+        assert_eq!(2, day.entries.len());
+        assert_eq!(
+            vec![
+                "line 2: unexpected input \"b cdefg\"",
+                "line 4: unexpected input \"x\""
+            ],
+            day.rejected_lines()
+        );
+        assert_eq!(day.rejected_lines(), &day.diagnostics()[..]);
+        assert_eq!(Some(2), day.puzzle_00());
+
+        day.parse_input(EXAMPLE);
+        assert!(day.rejected_lines().is_empty());
+    }
+
+    #[test]
+    fn whitespace_around_fields() {
+        let mut day = Day::default();
+        day.parse_input("1-3 a :  abcde \n1-3 b: cdefg\t\r\n2-9 c:ccccccccc");
+        assert!(day.rejected_lines().is_empty());
+        assert_eq!(Some(2), day.puzzle_00());
+    }
 }
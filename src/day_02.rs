@@ -1,97 +1,718 @@
 use crate::common::*;
-use itertools::iterate;
-use std::ops::Add;
+use std::cmp::Ordering;
+use std::collections::HashSet;
+use std::fs;
+use std::io;
 
 #[derive(Default)]
 pub struct Day {
     map: Map,
+    parse_error: Option<String>,
+    topology: Topology,
 }
 
 impl AocDay for Day {
     fn parse_input(&mut self, input: &str) {
-        self.map = input.into();
+        match parse_map(input) {
+            Ok(map) => {
+                self.map = map;
+                self.parse_error = None;
+            }
+            Err(error) => {
+                self.map = Map::default();
+                self.parse_error = Some(error.to_string());
+            }
+        }
     }
 
     fn puzzle_00(&self) -> Option<AocPuzzleAnswer> {
+        if self.parse_error.is_some() {
+            return None;
+        }
+
         let direction = Vec2(3, 1);
-        Some(count_trees(&self.map, direction))
+        Some(count_trees(&self.map, direction, self.topology))
     }
 
     fn puzzle_01(&self) -> Option<AocPuzzleAnswer> {
+        if self.parse_error.is_some() {
+            return None;
+        }
+
         let directions = [Vec2(1, 1), Vec2(3, 1), Vec2(5, 1), Vec2(7, 1), Vec2(1, 2)];
-        Some(multiply_trees_on_slopes(&self.map, &directions))
+        Some(multiply_trees_on_slopes(
+            &self.map,
+            &directions,
+            self.topology,
+        ))
+    }
+
+    fn diagnostics(&self) -> Vec<String> {
+        self.parse_error.iter().cloned().collect()
+    }
+
+    /// `edge_x` and `edge_y`, one of `wrap`, `clamp`, `reflect` and `stop`
+    fn set_option(&mut self, name: &str, value: &str) -> Result<(), String> {
+        let edge = match name {
+            "edge_x" => &mut self.topology.x,
+            "edge_y" => &mut self.topology.y,
+            _ => return Err(format!("unknown option {:?}", name)),
+        };
+        *edge = Edge::from_name(value).ok_or_else(|| {
+            format!(
+                "unknown edge {:?}, expected one of {}",
+                value,
+                Edge::ALL
+                    .iter()
+                    .map(|edge| edge.name())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        })?;
+        Ok(())
     }
 }
 
-#[derive(Copy, Clone)]
-pub struct Vec2(usize, usize);
+impl Day {
+    /// see `search_slopes`, `None` if the map couldn't be read
+    /// or the edges were changed from the puzzle's ones
+    pub fn search_slopes(&self, max_step: usize) -> Option<SlopeSearch> {
+        if self.parse_error.is_some() || self.topology != Topology::TOBOGGAN {
+            return None;
+        }
+        search_slopes(&self.map, max_step)
+    }
+
+    /// ride down by `step` over the map read last, `None` if it couldn't be read
+    pub fn renderer(&self, step: Vec2) -> Option<Renderer<'_>> {
+        if self.parse_error.is_some() {
+            return None;
+        }
+        Some(Renderer::new(&self.map, step).topology(self.topology))
+    }
+}
+
+/// what happens to the toboggan when it gets past the map along one axis
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Edge {
+    /// comes back in on the other side
+    Wrap,
+    /// slides along the edge
+    Clamp,
+    /// bounces off the edge and heads back
+    Reflect,
+    /// ride is over
+    Stop,
+}
+
+impl Edge {
+    pub const ALL: [Edge; 4] = [Edge::Wrap, Edge::Clamp, Edge::Reflect, Edge::Stop];
 
-impl Add for Vec2 {
-    type Output = Self;
+    pub fn name(self) -> &'static str {
+        match self {
+            Edge::Wrap => "wrap",
+            Edge::Clamp => "clamp",
+            Edge::Reflect => "reflect",
+            Edge::Stop => "stop",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|edge| edge.name() == name)
+    }
 
-    fn add(self, rhs: Self) -> Self::Output {
-        Self(self.0 + rhs.0, self.1 + rhs.1)
+    /// coordinate and velocity after moving `velocity` from `at` along an axis `len` cells long,
+    /// `None` once the ride is over
+    fn advance(self, at: isize, velocity: isize, len: usize) -> Option<(isize, isize)> {
+        let last = len as isize - 1;
+        let to = at + velocity;
+        match self {
+            _ if (0..=last).contains(&to) => Some((to, velocity)),
+            Edge::Wrap => Some((to.rem_euclid(len as isize), velocity)),
+            Edge::Clamp => Some((to.clamp(0, last), velocity)),
+            Edge::Reflect if last == 0 => Some((0, -velocity)),
+            Edge::Reflect => {
+                let (mut to, mut velocity) = (to, velocity);
+                while !(0..=last).contains(&to) {
+                    to = if to < 0 { -to } else { 2 * last - to };
+                    velocity = -velocity;
+                }
+                Some((to, velocity))
+            }
+            Edge::Stop => None,
+        }
     }
 }
 
-#[derive(Default)]
-struct Map {
-    rows: Vec<Vec<bool>>,
-}
-
-impl From<&str> for Map {
-    fn from(s: &str) -> Self {
-        let rows = s
-            .lines()
-            .map(|line| {
-                line.trim()
-                    .chars()
-                    .map(|c| match c {
-                        '.' => false,
-                        '#' => true,
-                        _ => unreachable!("Unexpected symbol in string!"),
-                    })
-                    .collect::<Vec<_>>()
-            })
-            .collect::<Vec<_>>();
+/// edge behavior along each axis
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Topology {
+    pub x: Edge,
+    pub y: Edge,
+}
+
+impl Topology {
+    /// the puzzle's map, repeating to the right until the toboggan reaches the bottom
+    pub const TOBOGGAN: Topology = Topology {
+        x: Edge::Wrap,
+        y: Edge::Stop,
+    };
+}
 
-        Map { rows }
+impl Default for Topology {
+    fn default() -> Self {
+        Self::TOBOGGAN
     }
 }
 
-impl Map {
-    fn w(&self) -> usize {
-        self.rows
-            .first()
-            .expect("Can't get the width of an empty map!")
-            .len()
+/// positions visited riding down `map` by `step` at a time, starting from the corner
+/// the slope points away from, so a negative `y` starts at the bottom;
+/// rides that never get off the map end before going around in circles
+pub struct Path<'m> {
+    map: &'m Map,
+    topology: Topology,
+    at: Option<Vec2>,
+    velocity: Vec2,
+    /// states ridden through, only kept for rides that can go around in circles
+    seen: Option<HashSet<(Vec2, Vec2)>>,
+}
+
+impl<'m> Path<'m> {
+    pub fn new(map: &'m Map, step: Vec2, topology: Topology) -> Self {
+        let start = |step: isize, len: usize| if step < 0 { len as isize - 1 } else { 0 };
+        let at = Vec2(start(step.0, map.width()), start(step.1, map.height()));
+        // moving towards a `Stop` edge gets off the map sooner or later,
+        // speeds never change so that's the case for the whole ride
+        let stops = |edge: Edge, velocity: isize| edge == Edge::Stop && velocity != 0;
+        let ends = stops(topology.x, step.0) || stops(topology.y, step.1);
+        Self {
+            map,
+            topology,
+            at: map.get(at).map(|_| at),
+            velocity: step,
+            seen: if ends { None } else { Some(HashSet::new()) },
+        }
     }
+}
 
-    fn h(&self) -> usize {
-        self.rows.len()
+impl Iterator for Path<'_> {
+    type Item = Vec2;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let at = self.at?;
+        if let Some(seen) = &mut self.seen {
+            seen.insert((at, self.velocity));
+        }
+
+        let x = self
+            .topology
+            .x
+            .advance(at.0, self.velocity.0, self.map.width());
+        let y = self
+            .topology
+            .y
+            .advance(at.1, self.velocity.1, self.map.height());
+        self.at = match (x, y) {
+            (Some((x, dx)), Some((y, dy)))
+                if !self
+                    .seen
+                    .as_ref()
+                    .is_some_and(|seen| seen.contains(&(Vec2(x, y), Vec2(dx, dy)))) =>
+            {
+                self.velocity = Vec2(dx, dy);
+                Some(Vec2(x, y))
+            }
+            _ => None,
+        };
+        Some(at)
     }
+}
 
-    fn in_bounds(&self, pos: Vec2) -> bool {
-        // world wraps around itself on X plane
-        pos.1 < self.h()
+type Map = Grid<bool>;
+
+fn parse_map(input: &str) -> Result<Map, GridError<&'static str>> {
+    Grid::parse_trimmed(input, |c| match c {
+        '.' => Ok(false),
+        '#' => Ok(true),
+        _ => Err("is neither open square nor tree"),
+    })
+}
+
+fn count_trees(map: &Map, step: Vec2, topology: Topology) -> usize {
+    Path::new(map, step, topology)
+        .filter(|&pos| map[pos])
+        .count()
+}
+
+fn multiply_trees_on_slopes(map: &Map, steps: &[Vec2], topology: Topology) -> usize {
+    steps
+        .iter()
+        .map(|&step| count_trees(map, step, topology))
+        .product()
+}
+
+const ANSI_TREE_HIT: &str = "\x1b[1;31mX\x1b[0m";
+const ANSI_OPEN_HIT: &str = "\x1b[1;32mO\x1b[0m";
+
+/// map drawn with a ride down it on top, `X` for trees hit and `O` for open squares,
+/// a map repeating sideways is drawn as many times over as the ride needs
+pub struct Renderer<'m> {
+    map: &'m Map,
+    step: Vec2,
+    topology: Topology,
+    colors: bool,
+}
+
+impl<'m> Renderer<'m> {
+    pub fn new(map: &'m Map, step: Vec2) -> Self {
+        Self {
+            map,
+            step,
+            topology: Topology::TOBOGGAN,
+            colors: false,
+        }
     }
 
-    fn wrap(&self, pos: Vec2) -> Vec2 {
-        Vec2(pos.0 % self.w(), pos.1)
+    pub fn topology(mut self, topology: Topology) -> Self {
+        self.topology = topology;
+        self
     }
 
-    fn at(&self, pos: Vec2) -> bool {
-        self.rows[pos.1][pos.0]
+    /// hits highlighted with ANSI escapes, for terminals rather than files
+    pub fn colors(mut self, colors: bool) -> Self {
+        self.colors = colors;
+        self
     }
+
+    pub fn render(&self) -> String {
+        let width = self.map.width() as isize;
+        let mut path = Path::new(self.map, self.step, self.topology).enumerate();
+        let first = path.next();
+
+        // wrapping keeps the sideways velocity, so the k-th cell is k steps from the first
+        let mut hits = HashSet::new();
+        for (k, Vec2(x, y)) in first.into_iter().chain(path) {
+            let x = match (self.topology.x, first) {
+                (Edge::Wrap, Some((_, start))) => start.0 + k as isize * self.step.0,
+                _ => x,
+            };
+            hits.insert((x, y));
+        }
+
+        let tile = |x: isize| x.div_euclid(width.max(1));
+        let left = hits.iter().map(|&(x, _)| tile(x)).min().unwrap_or(0);
+        let right = hits.iter().map(|&(x, _)| tile(x)).max().unwrap_or(0);
+
+        let mut out = String::new();
+        for (y, row) in self.map.rows().enumerate() {
+            for x in left * width..(right + 1) * width {
+                let tree = row[x.rem_euclid(width) as usize];
+                match (hits.contains(&(x, y as isize)), tree) {
+                    (true, true) if self.colors => out.push_str(ANSI_TREE_HIT),
+                    (true, false) if self.colors => out.push_str(ANSI_OPEN_HIT),
+                    (true, true) => out.push('X'),
+                    (true, false) => out.push('O'),
+                    (false, true) => out.push('#'),
+                    (false, false) => out.push('.'),
+                }
+            }
+            out.push('\n');
+        }
+        out
+    }
+
+    pub fn save<P: AsRef<std::path::Path>>(&self, path: P) -> io::Result<()> {
+        fs::write(path, self.render())
+    }
+}
+
+/// slopes that got the fewest or the most trees, all of them in case of a tie
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Extreme {
+    pub trees: usize,
+    pub slopes: Vec<Vec2>,
+}
+
+impl Extreme {
+    fn new() -> Self {
+        Self {
+            trees: 0,
+            slopes: Vec::new(),
+        }
+    }
+
+    /// takes `slope` if it has as many trees as the ones so far, or `wanted` compared to them
+    fn consider(&mut self, slope: Vec2, trees: usize, wanted: Ordering) {
+        match trees.cmp(&self.trees) {
+            Ordering::Equal => self.slopes.push(slope),
+            ordering if ordering == wanted || self.slopes.is_empty() => {
+                self.trees = trees;
+                self.slopes = vec![slope];
+            }
+            _ => {}
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SlopeSearch {
+    pub fewest: Extreme,
+    pub most: Extreme,
 }
 
-fn count_trees(map: &Map, step: Vec2) -> usize {
-    iterate(Vec2(0, 0), |&x| map.wrap(x + step))
-        .take_while(|&x| map.in_bounds(x))
-        .filter(|&x| map.at(x))
+/// every slope down the puzzle's map going at most `max_step` cells along either axis per step,
+/// one per direction, so `Vec2(2, 2)` is left out for `Vec2(1, 1)`; `None` for an empty map
+///
+/// the map repeats to the right and the ride can't go in circles, so paths are counted
+/// straight over the rows they take instead of riding them down with `Path`; the rows every
+/// `dy` takes are picked once for all of its slopes, slopes whose `dx` only differ by
+/// the width of the map are counted once, and so are all `dy` reaching past the bottom
+/// in one step, which only ever visit the first row
+pub fn search_slopes(map: &Map, max_step: usize) -> Option<SlopeSearch> {
+    if map.width() == 0 || max_step == 0 {
+        return None;
+    }
+
+    let width = map.width();
+    let max = max_step as isize;
+    let mut fewest = Extreme::new();
+    let mut most = Extreme::new();
+    let mut rows = Vec::new();
+    // trees by shift, for paths starting from the left edge and from the right one
+    let mut counts = Vec::new();
+
+    for dy in 1..=max {
+        if dy as usize <= map.height() {
+            rows = map.rows().step_by(dy as usize).collect::<Vec<_>>();
+            counts = vec![[None; 2]; width];
+        }
+        for dx in (-max..=max).filter(|dx| gcd(dx.abs(), dy) == 1) {
+            // same start as `Path`, left edge unless heading left
+            let start = if dx < 0 { width - 1 } else { 0 };
+            let shift = dx.rem_euclid(width as isize) as usize;
+            let trees = *counts[shift][(dx < 0) as usize]
+                .get_or_insert_with(|| trees_on_path(&rows, start, shift));
+
+            fewest.consider(Vec2(dx, dy), trees, Ordering::Less);
+            most.consider(Vec2(dx, dy), trees, Ordering::Greater);
+        }
+    }
+
+    Some(SlopeSearch { fewest, most })
+}
+
+/// trees on `rows` starting at column `x`, moving `shift` columns right on each row
+fn trees_on_path(rows: &[&[bool]], mut x: usize, shift: usize) -> usize {
+    rows.iter()
+        .filter(|row| {
+            let tree = row[x];
+            x = (x + shift) % row.len();
+            tree
+        })
         .count()
 }
 
-fn multiply_trees_on_slopes(map: &Map, steps: &[Vec2]) -> usize {
-    steps.iter().map(|&step| count_trees(map, step)).product()
+fn gcd(a: isize, b: isize) -> isize {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXAMPLE: &str = "\
+..##.......
+#...#...#..
+.#....#..#.
+..#.#...#.#
+.#...##..#.
+..#.##.....
+.#.#.#....#
+.#........#
+#.##...#...
+#...##....#
+.#..#...#.#
+";
+
+    #[test]
+    fn example_slopes() {
+        let mut day = Day::default();
+        day.parse_input(EXAMPLE);
+        assert_eq!(Some(7), day.puzzle_00());
+        assert_eq!(Some(336), day.puzzle_01());
+    }
+
+    #[test]
+    fn malformed_maps_are_rejected() {
+        let mut day = Day::default();
+        day.parse_input("..#\n.x.");
+        assert_eq!(
+            Some("line 2:2: 'x' is neither open square nor tree".to_owned()),
+            day.parse_error
+        );
+        assert_eq!(None, day.puzzle_00());
+
+        day.parse_input("..#\n..");
+        assert_eq!(
+            Some("line 2: 2 cells wide, expected 3".to_owned()),
+            day.parse_error
+        );
+        assert_eq!(vec!["line 2: 2 cells wide, expected 3"], day.diagnostics());
+        assert_eq!(None, day.puzzle_01());
+
+        day.parse_input("\n  ..#\n\n  .x.\n");
+        assert_eq!(
+            Some("line 4:4: 'x' is neither open square nor tree".to_owned()),
+            day.parse_error
+        );
+
+        day.parse_input("\n  ..#  \r\n\t#..\n\n");
+        assert_eq!(None, day.parse_error);
+        assert_eq!(Some(1), day.puzzle_00());
+    }
+
+    #[test]
+    fn edges() {
+        assert_eq!(Some((3, 2)), Edge::Stop.advance(1, 2, 5));
+        assert_eq!(None, Edge::Stop.advance(4, 2, 5));
+        assert_eq!(None, Edge::Stop.advance(0, -1, 5));
+        assert_eq!(Some((1, 2)), Edge::Wrap.advance(4, 2, 5));
+        assert_eq!(Some((4, -2)), Edge::Wrap.advance(1, -2, 5));
+        assert_eq!(Some((4, 2)), Edge::Clamp.advance(3, 2, 5));
+        assert_eq!(Some((0, -7)), Edge::Clamp.advance(3, -7, 5));
+        assert_eq!(Some((2, -3)), Edge::Reflect.advance(3, 3, 5));
+        assert_eq!(Some((1, 2)), Edge::Reflect.advance(1, -2, 5));
+        assert_eq!(Some((4, 11)), Edge::Reflect.advance(1, 11, 5));
+        assert_eq!(Some((0, -1)), Edge::Reflect.advance(0, 1, 1));
+    }
+
+    #[test]
+    fn negative_slopes() {
+        let map = parse_map(EXAMPLE).unwrap();
+        let topology = Topology::TOBOGGAN;
+        for &(x, y) in &[(1, 1), (3, 1), (5, 1), (7, 1), (1, 2)] {
+            assert_eq!(
+                count_trees(&map.flip_horizontal(), Vec2(x, y), topology),
+                count_trees(&map, Vec2(-x, y), topology)
+            );
+            assert_eq!(
+                count_trees(&map.flip_vertical(), Vec2(x, y), topology),
+                count_trees(&map, Vec2(x, -y), topology)
+            );
+        }
+    }
+
+    #[test]
+    fn other_topologies() {
+        let map = parse_map("#..\n.#.\n..#").unwrap();
+        let path = |step, x, y| {
+            Path::new(&map, step, Topology { x, y })
+                .map(|Vec2(x, y)| (x, y))
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            vec![(0, 0), (2, 1)],
+            path(Vec2(2, 1), Edge::Stop, Edge::Stop)
+        );
+        assert_eq!(
+            vec![(0, 0), (2, 1), (2, 2)],
+            path(Vec2(2, 1), Edge::Clamp, Edge::Stop)
+        );
+        assert_eq!(
+            vec![(0, 0), (2, 1), (0, 2)],
+            path(Vec2(2, 1), Edge::Reflect, Edge::Stop)
+        );
+        // bounces around the map once before it would start repeating itself
+        assert_eq!(
+            vec![(0, 0), (1, 1), (2, 2), (1, 1), (0, 0)],
+            path(Vec2(1, 1), Edge::Reflect, Edge::Reflect)
+        );
+        // never gets off the map and never moves down, so it's over once it has gone around
+        assert_eq!(
+            vec![(0, 0), (1, 0), (2, 0)],
+            path(Vec2(1, 0), Edge::Wrap, Edge::Stop)
+        );
+        assert_eq!(vec![(0, 0)], path(Vec2(0, 0), Edge::Wrap, Edge::Wrap));
+        assert_eq!(3, count_trees(&map, Vec2(1, 1), Topology::TOBOGGAN));
+
+        // only rides that may never end keep track of where they have been
+        let tracked = |step, topology| Path::new(&map, step, topology).seen.is_some();
+        assert!(!tracked(Vec2(3, 1), Topology::TOBOGGAN));
+        assert!(!tracked(
+            Vec2(2, 0),
+            Topology {
+                x: Edge::Stop,
+                y: Edge::Wrap
+            }
+        ));
+        assert!(tracked(Vec2(1, 0), Topology::TOBOGGAN));
+        assert!(tracked(
+            Vec2(1, 1),
+            Topology {
+                x: Edge::Reflect,
+                y: Edge::Clamp
+            }
+        ));
+    }
+
+    #[test]
+    fn edges_selected_by_option() {
+        let mut day = Day::default();
+        day.parse_input(EXAMPLE);
+        day.set_option("edge_x", "stop").unwrap();
+        assert_eq!(Some(1), day.puzzle_00());
+        day.set_option("edge_x", "wrap").unwrap();
+        assert_eq!(Some(7), day.puzzle_00());
+
+        assert_eq!(
+            Err("unknown edge \"bounce\", expected one of wrap, clamp, reflect, stop".to_owned()),
+            day.set_option("edge_y", "bounce")
+        );
+        assert_eq!(
+            Err("unknown option \"edge_z\"".to_owned()),
+            day.set_option("edge_z", "wrap")
+        );
+    }
+
+    #[test]
+    fn slope_search() {
+        let map = parse_map(EXAMPLE).unwrap();
+        for max_step in 1..=25 {
+            let search = search_slopes(&map, max_step).unwrap();
+            let max = max_step as isize;
+            let counts = (1..=max)
+                .flat_map(|dy| (-max..=max).map(move |dx| Vec2(dx, dy)))
+                .filter(|step| gcd(step.0.abs(), step.1) == 1)
+                .map(|step| (step, count_trees(&map, step, Topology::TOBOGGAN)))
+                .collect::<Vec<_>>();
+
+            let fewest = counts.iter().map(|&(_, trees)| trees).min().unwrap();
+            let most = counts.iter().map(|&(_, trees)| trees).max().unwrap();
+            let with = |wanted| {
+                counts
+                    .iter()
+                    .filter(|&&(_, trees)| trees == wanted)
+                    .map(|&(step, _)| step)
+                    .collect::<Vec<_>>()
+            };
+            assert_eq!(fewest, search.fewest.trees);
+            assert_eq!(with(fewest), search.fewest.slopes);
+            assert_eq!(most, search.most.trees);
+            assert_eq!(with(most), search.most.slopes);
+        }
+
+        let search = search_slopes(&map, 1).unwrap();
+        assert_eq!(vec![Vec2(1, 1)], search.fewest.slopes);
+        assert_eq!(vec![Vec2(-1, 1), Vec2(0, 1)], search.most.slopes);
+        assert_eq!(None, search_slopes(&map, 0));
+        assert_eq!(None, search_slopes(&Map::default(), 3));
+
+        let mut day = Day::default();
+        day.parse_input(EXAMPLE);
+        assert_eq!(Some(search), day.search_slopes(1));
+        day.set_option("edge_x", "stop").unwrap();
+        assert_eq!(None, day.search_slopes(1));
+        day.set_option("edge_x", "wrap").unwrap();
+        day.parse_input("x");
+        assert_eq!(None, day.search_slopes(1));
+    }
+
+    /// ride from the puzzle statement, cut down to as much of the map as it crosses
+    const EXAMPLE_RIDE: &str = "\
+O.##.........##.........##.......
+#..O#...#..#...#...#..#...#...#..
+.#....X..#..#....#..#..#....#..#.
+..#.#...#O#..#.#...#.#..#.#...#.#
+.#...##..#..X...##..#..#...##..#.
+..#.##.......#.X#.......#.##.....
+.#.#.#....#.#.#.#.O..#.#.#.#....#
+.#........#.#........X.#........#
+#.##...#...#.##...#...#.X#...#...
+#...##....##...##....##...#X....#
+.#..#...#.#.#..#...#.#.#..#...X.#
+";
+
+    #[test]
+    fn rendered_rides() {
+        let map = parse_map(EXAMPLE).unwrap();
+        assert_eq!(EXAMPLE_RIDE, Renderer::new(&map, Vec2(3, 1)).render());
+
+        // heading left starts from the right edge, so the last copy of the map holds the start
+        let left = Renderer::new(&map, Vec2(-3, 1)).render();
+        let first_row = left.lines().next().unwrap();
+        assert_eq!(33, first_row.len());
+        assert_eq!(Some('O'), first_row.chars().last());
+
+        let stopped = Topology {
+            x: Edge::Stop,
+            y: Edge::Stop,
+        };
+        let rendered = Renderer::new(&map, Vec2(3, 1)).topology(stopped).render();
+        assert_eq!(
+            "O.##.......\n#..O#...#..\n.#....X..#.\n..#.#...#O#\n",
+            &rendered[..48]
+        );
+
+        let colored = Renderer::new(&map, Vec2(3, 1)).colors(true).render();
+        assert!(colored.starts_with("\x1b[1;32mO\x1b[0m.##."));
+        assert_eq!(7, colored.matches(ANSI_TREE_HIT).count());
+
+        let mut day = Day::default();
+        day.parse_input(EXAMPLE);
+        let path = std::env::temp_dir().join(format!("day_02_ride_{}.txt", std::process::id()));
+        day.renderer(Vec2(3, 1)).unwrap().save(&path).unwrap();
+        assert_eq!(EXAMPLE_RIDE, fs::read_to_string(&path).unwrap());
+        fs::remove_file(&path).unwrap();
+        day.parse_input("x");
+        assert!(day.renderer(Vec2(3, 1)).is_none());
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use test::{black_box, Bencher};
+
+    const MAX_STEP: usize = 16;
+
+    /// puzzle sized map with trees scattered around by a linear congruential generator
+    fn map() -> Map {
+        let mut state = 1u32;
+        let mut cells = Vec::new();
+        for _ in 0..31 * 323 {
+            state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+            cells.push(state >> 16 & 3 == 0);
+        }
+        Grid::from_cells(31, cells).unwrap()
+    }
+
+    #[bench]
+    fn slopes_searched(b: &mut Bencher) {
+        let map = map();
+        b.iter(|| black_box(search_slopes(&map, MAX_STEP)));
+    }
+
+    #[bench]
+    fn slopes_wider_than_the_map_searched(b: &mut Bencher) {
+        let map = map();
+        b.iter(|| black_box(search_slopes(&map, 4 * MAX_STEP)));
+    }
+
+    #[bench]
+    fn slopes_counted_one_by_one(b: &mut Bencher) {
+        let map = map();
+        let max = MAX_STEP as isize;
+        b.iter(|| {
+            (1..=max)
+                .flat_map(|dy| (-max..=max).map(move |dx| Vec2(dx, dy)))
+                .filter(|step| gcd(step.0.abs(), step.1) == 1)
+                .map(|step| count_trees(&map, step, Topology::TOBOGGAN))
+                .max()
+        });
+    }
 }
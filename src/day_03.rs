@@ -3,20 +3,48 @@ use crate::common::*;
 #[derive(Default)]
 pub struct Day {
     passport_data_groups: Vec<Vec<String>>,
+    parse_error: Option<String>,
 }
 
 impl AocDay for Day {
     fn parse_input(&mut self, input: &str) {
-        self.passport_data_groups = group_by_empty_lines(input);
+        match parse_passports(input) {
+            Ok(groups) => {
+                self.passport_data_groups = groups;
+                self.parse_error = None;
+            }
+            Err(error) => {
+                self.passport_data_groups = Vec::new();
+                self.parse_error = Some(error.to_string());
+            }
+        }
     }
 
     fn puzzle_00(&self) -> Option<AocPuzzleAnswer> {
+        if self.parse_error.is_some() {
+            return None;
+        }
+
         Some(count_valid_passports(&self.passport_data_groups))
     }
 
     fn puzzle_01(&self) -> Option<AocPuzzleAnswer> {
+        if self.parse_error.is_some() {
+            return None;
+        }
+
         Some(count_valid_passports_ex(&self.passport_data_groups))
     }
+
+    fn diagnostics(&self) -> Vec<String> {
+        self.parse_error.iter().cloned().collect()
+    }
+}
+
+/// `key:value` fields of every passport, separated by any whitespace
+fn parse_passports(input: &str) -> Result<Vec<Vec<String>>, LineError<&str>> {
+    let field = map(take_while1(|c: char| !c.is_whitespace()), String::from);
+    blocks_of(right(space_0(), one_or_more(left(field, space_0()))))(input)
 }
 
 bitflags! {
@@ -87,15 +115,11 @@ fn is_valid_height(string: &str) -> bool {
 }
 
 fn is_valid_hair_color(string: &str) -> bool {
-    if let Some('#') = string.chars().nth(0) {
-        let rest: String = string.chars().skip(1).collect();
-        rest.len() == 6
-            && rest
-                .chars()
-                .all(|c| c.is_ascii_digit() || (c >= 'a' && c <= 'f'))
-    } else {
-        false
-    }
+    let color = right(
+        match_literal("#"),
+        pred(class1(('0'..='9', 'a'..='f')), |digits| digits.len() == 6),
+    );
+    all_consuming(color).parse(string).is_ok()
 }
 
 fn is_valid_eye_color(string: &str) -> bool {
@@ -104,11 +128,13 @@ fn is_valid_eye_color(string: &str) -> bool {
 }
 
 fn is_valid_passport_number(string: &str) -> bool {
-    string.len() == 9 && string.chars().all(|c| c.is_ascii_digit())
+    all_consuming(pred(digit1(), |digits| digits.len() == 9))
+        .parse(string)
+        .is_ok()
 }
 
 fn str_to_field_with_validation(string: &str) -> Option<u32> {
-    let data = string.split(':').nth(1).unwrap();
+    let data = string.split(':').nth(1).unwrap_or("");
     let valid = if let Some(field) = str_to_field(string) {
         match field {
             Fields::BYR => is_valid_byr(data),
@@ -131,22 +157,22 @@ fn str_to_field_with_validation(string: &str) -> Option<u32> {
     }
 }
 
-fn count_valid_passports(data_groups: &Vec<Vec<String>>) -> usize {
+fn count_valid_passports(data_groups: &[Vec<String>]) -> usize {
     data_groups
         .iter()
-        .map(|group| group.iter().flat_map(|line| line.split(' ')))
-        .map(|group| group.map(str_to_field_u32).fold(0, |acc, x| acc | x))
+        .map(|group| group.iter().map(|field| str_to_field_u32(field)))
+        .map(|group| group.fold(0, |acc, x| acc | x))
         .filter(|&x| x & Fields::ALL.bits == Fields::ALL.bits)
         .count()
 }
 
-fn count_valid_passports_ex(data_groups: &Vec<Vec<String>>) -> usize {
+fn count_valid_passports_ex(data_groups: &[Vec<String>]) -> usize {
     data_groups
         .iter()
-        .map(|group| group.iter().flat_map(|line| line.split(' ')))
         .map(|group| {
             group
-                .filter_map(str_to_field_with_validation)
+                .iter()
+                .filter_map(|field| str_to_field_with_validation(field))
                 .fold(0, |acc, x| acc | x)
         })
         .filter(|&x| x & Fields::ALL.bits == Fields::ALL.bits)
@@ -159,11 +185,20 @@ mod tests {
 
     #[test]
     fn count_valid_passports_ex_example() {
-        let data_groups = group_by_empty_lines(EXAMPLE_INPUT);
+        let data_groups = parse_passports(EXAMPLE_INPUT).unwrap();
         let answer = count_valid_passports_ex(&data_groups);
         assert_eq!(answer, 2);
     }
 
+    #[test]
+    fn stray_whitespace_is_fine() {
+        let input = EXAMPLE_INPUT.replace('\n', " \n\t").replace(' ', "  ");
+        let mut day = Day::default();
+        day.parse_input(&input);
+        assert_eq!(None, day.parse_error);
+        assert_eq!(Some(2), day.puzzle_01());
+    }
+
     #[test]
     fn passport_field_validation() {
         assert!(is_valid_byr("2002"));
@@ -177,12 +212,15 @@ mod tests {
         assert!(is_valid_hair_color("#123abc"));
         assert!(!is_valid_hair_color("#123abz"));
         assert!(!is_valid_hair_color("123abc"));
+        assert!(!is_valid_hair_color("#123ABC"));
+        assert!(!is_valid_hair_color("#123abcd"));
 
         assert!(is_valid_eye_color("brn"));
         assert!(!is_valid_eye_color("wat"));
 
         assert!(is_valid_passport_number("000000001"));
         assert!(!is_valid_passport_number("0123456789"));
+        assert!(!is_valid_passport_number("01234567a"));
     }
 
     const EXAMPLE_INPUT: &'static str = "\
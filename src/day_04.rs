@@ -3,23 +3,60 @@ use itertools::Itertools;
 
 #[derive(Default)]
 pub struct Day {
-    input: String,
+    seat_ids: Vec<u32>,
+    parse_error: Option<String>,
 }
 
 impl AocDay for Day {
     fn parse_input(&mut self, input: &str) {
-        self.input = input.into();
+        match parse_seat_ids(input) {
+            Ok(seat_ids) => {
+                self.seat_ids = seat_ids;
+                self.parse_error = None;
+            }
+            Err(error) => {
+                self.seat_ids = Vec::new();
+                self.parse_error = Some(error.to_string());
+            }
+        }
     }
 
     fn puzzle_00(&self) -> Option<AocPuzzleAnswer> {
-        Some(find_max_seat_id(&self.input) as usize)
+        if self.parse_error.is_some() {
+            return None;
+        }
+
+        find_max_seat_id(&self.seat_ids).map(|id| id as usize)
     }
 
     fn puzzle_01(&self) -> Option<AocPuzzleAnswer> {
-        Some(find_my_seat_id(&self.input) as usize)
+        if self.parse_error.is_some() {
+            return None;
+        }
+
+        find_my_seat_id(&self.seat_ids).map(|id| id as usize)
+    }
+
+    fn diagnostics(&self) -> Vec<String> {
+        self.parse_error.iter().cloned().collect()
     }
 }
 
+/// seat of every boarding pass like `FBFBBFFRLR`, blank lines are skipped
+fn parse_seat_ids(input: &str) -> Result<Vec<u32>, LineError<&str>> {
+    let run = |class, len| {
+        pred(
+            recognize(one_or_more(one_of(class))),
+            move |letters: &&str| letters.len() == len,
+        )
+    };
+    let boarding_pass = recognize(pair(run("FB", 7), run("LR", 3)));
+    lines_of(right(
+        space_0(),
+        left(map(boarding_pass, ticket_id_to_seat_id), space_0()),
+    ))(input)
+}
+
 enum Direction {
     Left,
     Right,
@@ -84,25 +121,20 @@ fn ticket_id_to_seat_id(ticket_id: &str) -> u32 {
     seat_id(row, col)
 }
 
-fn find_max_seat_id(input: &str) -> u32 {
-    input.lines().map(ticket_id_to_seat_id).max().unwrap()
+fn find_max_seat_id(seat_ids: &[u32]) -> Option<u32> {
+    seat_ids.iter().copied().max()
 }
 
-fn find_my_seat_id(input: &str) -> u32 {
-    let seat_ids = input
-        .lines()
-        .map(ticket_id_to_seat_id)
-        .sorted()
-        .collect::<Vec<u32>>();
+fn find_my_seat_id(seat_ids: &[u32]) -> Option<u32> {
+    let seat_ids = seat_ids.iter().copied().sorted().collect::<Vec<u32>>();
 
     let (prev_seat, _) = seat_ids
         .windows(2)
         .map(|window| (window[0], window[1]))
-        .find(|(prev, next)| next - prev == 2)
-        .unwrap();
+        .find(|(prev, next)| next - prev == 2)?;
 
     let my_seat_id = prev_seat + 1;
-    my_seat_id
+    Some(my_seat_id)
 }
 
 #[cfg(test)]
@@ -128,4 +160,26 @@ mod tests {
         assert_eq!(ticket_id_to_seat_id("FFFBBBFRRR"), 119);
         assert_eq!(ticket_id_to_seat_id("BBFFBBFRLL"), 820);
     }
+
+    #[test]
+    fn malformed_passes() {
+        let mut day = Day::default();
+        day.parse_input("BFFFBBFRRR\n FFFBBBFRRR\n\nBBFFBBFRLL \n");
+        assert_eq!(Some(820), day.puzzle_00());
+        assert_eq!(None, day.puzzle_01());
+
+        day.parse_input("BFFFBBFRRR\nFFFBBBFRLX");
+        assert_eq!(
+            Some("line 2: unexpected input \"RLX\"".to_owned()),
+            day.parse_error
+        );
+        assert_eq!(
+            day.parse_error.iter().cloned().collect::<Vec<_>>(),
+            day.diagnostics()
+        );
+        assert_eq!(None, day.puzzle_00());
+
+        day.parse_input("");
+        assert_eq!(None, day.puzzle_00());
+    }
 }
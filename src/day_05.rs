@@ -4,23 +4,51 @@ use std::collections::HashSet;
 #[derive(Default)]
 pub struct Day {
     answer_groups: Vec<Vec<String>>,
+    parse_error: Option<String>,
 }
 
 impl AocDay for Day {
     fn parse_input(&mut self, input: &str) {
-        self.answer_groups = group_by_empty_lines(input);
+        match parse_answer_groups(input) {
+            Ok(groups) => {
+                self.answer_groups = groups;
+                self.parse_error = None;
+            }
+            Err(error) => {
+                self.answer_groups = Vec::new();
+                self.parse_error = Some(error.to_string());
+            }
+        }
     }
 
     fn puzzle_00(&self) -> Option<AocPuzzleAnswer> {
+        if self.parse_error.is_some() {
+            return None;
+        }
+
         Some(count_unique_answers(&self.answer_groups))
     }
 
     fn puzzle_01(&self) -> Option<AocPuzzleAnswer> {
+        if self.parse_error.is_some() {
+            return None;
+        }
+
         Some(count_unanimous_answers(&self.answer_groups))
     }
+
+    fn diagnostics(&self) -> Vec<String> {
+        self.parse_error.iter().cloned().collect()
+    }
+}
+
+/// answers of every person in every group
+fn parse_answer_groups(input: &str) -> Result<Vec<Vec<String>>, LineError<&str>> {
+    let person = map(right(space_0(), word_str()), String::from);
+    blocks_of(left(one_or_more(person), space_0()))(input)
 }
 
-fn count_unique_answers(answer_groups: &Vec<Vec<String>>) -> usize {
+fn count_unique_answers(answer_groups: &[Vec<String>]) -> usize {
     answer_groups
         .iter()
         .map(|lines| {
@@ -32,7 +60,7 @@ fn count_unique_answers(answer_groups: &Vec<Vec<String>>) -> usize {
         .sum()
 }
 
-fn count_unanimous_answers(answer_groups: &Vec<Vec<String>>) -> usize {
+fn count_unanimous_answers(answer_groups: &[Vec<String>]) -> usize {
     answer_groups
         .iter()
         .map(|lines| {
@@ -55,11 +83,17 @@ mod tests {
 
     #[test]
     fn unanimous_answers() {
-        assert_eq!(count_unanimous_answers(&group_by_empty_lines("a\nb\nc")), 0);
+        assert_eq!(
+            count_unanimous_answers(&parse_answer_groups("a\nb\nc").unwrap()),
+            0
+        );
         let input = "\
     ab
     ac";
-        assert_eq!(count_unanimous_answers(&group_by_empty_lines(input)), 1);
+        assert_eq!(
+            count_unanimous_answers(&parse_answer_groups(input).unwrap()),
+            1
+        );
         let input = "\
     abc
 
@@ -76,6 +110,22 @@ mod tests {
     a
 
     b";
-        assert_eq!(count_unanimous_answers(&group_by_empty_lines(input)), 6);
+        assert_eq!(
+            count_unanimous_answers(&parse_answer_groups(input).unwrap()),
+            6
+        );
+    }
+
+    #[test]
+    fn malformed_groups() {
+        let mut day = Day::default();
+        day.parse_input("abc\n\na\nb!\n");
+        assert_eq!(None, day.puzzle_00());
+        assert_eq!(None, day.puzzle_01());
+        assert!(day.parse_error.is_some());
+
+        day.parse_input("abc\n\na\nb\n");
+        assert_eq!(Some(5), day.puzzle_00());
+        assert_eq!(None, day.parse_error);
     }
 }
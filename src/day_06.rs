@@ -1,27 +1,54 @@
 use crate::common::*;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 
 const GOLDEN_COLOR: &str = "shiny gold";
 
 #[derive(Default)]
 pub struct Day {
     bags_spec: HashMap<String, Vec<BagChild>>,
+    parse_error: Option<String>,
+    unknown_colors: Vec<Located<String>>,
 }
 
 impl AocDay for Day {
     fn parse_input(&mut self, input: &str) {
-        self.bags_spec = input
-            .lines()
-            .filter_map(|line| bag_parser().parse(line).ok())
-            .map(|(_, bag)| (bag.color, bag.children))
+        let bags = match lines_of(bag_parser(input))(input) {
+            Ok(bags) => {
+                self.parse_error = None;
+                bags
+            }
+            Err(error) => {
+                self.parse_error = Some(error.to_string());
+                Vec::new()
+            }
+        };
+
+        self.unknown_colors = find_unknown_colors(&bags)
+            .into_iter()
+            .map(|color| color.map(String::from))
+            .collect();
+        self.bags_spec = bags
+            .into_iter()
+            .map(|bag| {
+                let children = bag
+                    .children
+                    .into_iter()
+                    .map(|(count, color)| (count, color.value.to_owned()))
+                    .collect();
+                (bag.color.to_owned(), children)
+            })
             .collect();
     }
 
     fn puzzle_00(&self) -> Option<AocPuzzleAnswer> {
+        if !self.is_valid() {
+            return None;
+        }
+
         let answer = self
             .bags_spec
             .iter()
-            .map(|x| self.will_contain_golden_bag(x.0))
+            .map(|x| self.will_contain_golden_bag(x.0, &mut HashSet::new()))
             .filter(|x| *x)
             .count();
 
@@ -29,29 +56,67 @@ impl AocDay for Day {
     }
 
     fn puzzle_01(&self) -> Option<AocPuzzleAnswer> {
-        Some(self.count_contained_bags(GOLDEN_COLOR))
+        if !self.is_valid() || !self.bags_spec.contains_key(GOLDEN_COLOR) {
+            return None;
+        }
+
+        self.count_contained_bags(GOLDEN_COLOR, &mut Vec::new())
+    }
+
+    fn diagnostics(&self) -> Vec<String> {
+        let unknown_colors = self
+            .unknown_colors
+            .iter()
+            .map(|color| format!("unknown color {}", color));
+        self.parse_error
+            .iter()
+            .cloned()
+            .chain(unknown_colors)
+            .collect()
     }
 }
 
 impl Day {
-    fn will_contain_golden_bag(&self, color: &str) -> bool {
+    fn is_valid(&self) -> bool {
+        self.parse_error.is_none() && self.unknown_colors.is_empty()
+    }
+
+    /// `seen` colors were looked into already, rules may contain each other in a loop
+    fn will_contain_golden_bag<'s>(&'s self, color: &'s str, seen: &mut HashSet<&'s str>) -> bool {
+        if !seen.insert(color) {
+            return false;
+        }
+
         let contents = &self.bags_spec[color];
         if contents.iter().any(|x| x.1 == GOLDEN_COLOR) {
             true
         } else {
             contents
                 .iter()
-                .map(|x| self.will_contain_golden_bag(&x.1))
+                .map(|x| self.will_contain_golden_bag(&x.1, seen))
                 .any(|x| x)
         }
     }
 
-    fn count_contained_bags(&self, color: &str) -> AocPuzzleAnswer {
+    /// `None` if a bag ends up inside of itself, `outer` ones can't be counted,
+    /// or if there are too many of them
+    fn count_contained_bags<'s>(
+        &'s self,
+        color: &'s str,
+        outer: &mut Vec<&'s str>,
+    ) -> Option<AocPuzzleAnswer> {
+        if outer.contains(&color) {
+            return None;
+        }
+
+        outer.push(color);
         let contents = &self.bags_spec[color];
-        contents
-            .iter()
-            .map(|x| x.0 as AocPuzzleAnswer * (self.count_contained_bags(&x.1) + 1))
-            .sum()
+        let count = contents.iter().try_fold(0 as AocPuzzleAnswer, |sum, x| {
+            let inner = self.count_contained_bags(&x.1, outer)?.checked_add(1)?;
+            sum.checked_add((x.0 as AocPuzzleAnswer).checked_mul(inner)?)
+        });
+        outer.pop();
+        count
     }
 }
 
@@ -59,25 +124,43 @@ type BagColor = String;
 type BagChild = (u32, BagColor);
 
 #[derive(Debug, Eq, PartialEq)]
-struct BagContents {
-    color: BagColor,
-    children: Vec<BagChild>,
+struct BagContents<'a> {
+    color: &'a str,
+    children: Vec<(u32, Located<&'a str>)>,
 }
 
-fn color_parser<'a>() -> impl Parser<'a, String> {
-    join(
-        one_or_more_until(left(word(), space_1()), match_literal("bag")),
-        " ",
-    )
+/// children referencing colors that don't have a rule of their own
+fn find_unknown_colors<'a>(bags: &[BagContents<'a>]) -> Vec<Located<&'a str>> {
+    let known_colors = bags.iter().map(|bag| bag.color).collect::<HashSet<_>>();
+    bags.iter()
+        .flat_map(|bag| bag.children.iter().map(|(_, color)| *color))
+        .filter(|color| !known_colors.contains(color.value))
+        .collect()
+}
+
+/// words up to, but not including, the space before `bag`
+fn color_parser<'a>() -> impl Parser<'a, &'a str> {
+    let words = recognize(one_or_more_until(
+        trace("color word", left(word_str(), space_1())),
+        match_literal("bag"),
+    ));
+    trace("color", move |input: &'a str| {
+        let (_, words) = words.parse(input)?;
+        let color = words.trim_end();
+        Ok((&input[color.len()..], color))
+    })
 }
 
-fn child_parser<'a>() -> impl Parser<'a, BagChild> {
-    pair(left(number(), space_1()), color_parser())
+fn child_parser<'a>(source: &'a str) -> impl Parser<'a, (u32, Located<&'a str>)> {
+    pair(
+        left(number(), space_1()),
+        left(spanned(source, color_parser()), space_1()),
+    )
 }
 
-fn children_parser<'a>() -> impl Parser<'a, Vec<BagChild>> {
+fn children_parser<'a>(source: &'a str) -> impl Parser<'a, Vec<(u32, Located<&'a str>)>> {
     one_or_more(left(
-        child_parser(),
+        child_parser(source),
         pair(
             either(match_literal("bags"), match_literal("bag")),
             either(match_literal(", "), match_literal(".")),
@@ -85,12 +168,12 @@ fn children_parser<'a>() -> impl Parser<'a, Vec<BagChild>> {
     ))
 }
 
-fn bag_parser<'a>() -> impl Parser<'a, BagContents> {
+fn bag_parser<'a>(source: &'a str) -> impl Parser<'a, BagContents<'a>> {
     map(
         pair(
-            left(color_parser(), match_literal("bags contain ")),
+            left(color_parser(), match_literal(" bags contain ")),
             either(
-                children_parser(),
+                children_parser(source),
                 map(match_literal("no other bags."), |_| Vec::new()),
             ),
         ),
@@ -102,33 +185,147 @@ fn bag_parser<'a>() -> impl Parser<'a, BagContents> {
 mod tests {
     use super::*;
 
+    fn located(source: &str, start: usize, end: usize) -> Located<&str> {
+        Located {
+            value: &source[start..end],
+            span: Span::new(source, start, end),
+        }
+    }
+
     #[test]
     fn bag_colors_parsing() {
         let input = "pale turquoise bags contain 3 muted cyan bags, 5 striped teal bags.";
         assert_eq!(
             Ok((
-                "bags contain 3 muted cyan bags, 5 striped teal bags.",
-                "pale turquoise".into()
+                " bags contain 3 muted cyan bags, 5 striped teal bags.",
+                "pale turquoise"
             )),
             color_parser().parse(input)
         );
+        let child = "5 striped teal bags.";
         assert_eq!(
-            Ok(("bags.", (5, "striped teal".into()))),
-            child_parser().parse("5 striped teal bags.")
+            Ok(("bags.", (5, located(child, 2, 14)))),
+            child_parser(child).parse(child)
         );
         assert_eq!(
-            Ok(("", vec![(5, "striped teal".into())])),
-            children_parser().parse("5 striped teal bags.")
+            Ok(("", vec![(5, located(child, 2, 14))])),
+            children_parser(child).parse(child)
         );
         assert_eq!(
             Ok((
                 "",
                 BagContents {
-                    color: "pale turquoise".into(),
-                    children: vec![(3, "muted cyan".into()), (5, "striped teal".into())]
+                    color: "pale turquoise",
+                    children: vec![(3, located(input, 30, 40)), (5, located(input, 49, 61))]
                 }
             )),
-            bag_parser().parse(input)
+            bag_parser(input).parse(input)
+        );
+    }
+
+    #[test]
+    fn unknown_colors_are_reported() {
+        let input = "\
+light red bags contain 1 bright white bag, 2 muted yellow bags.
+bright white bags contain 1 shiny gold bag.
+shiny gold bags contain no other bags.";
+
+        let mut day = Day::default();
+        day.parse_input(input);
+        assert_eq!(
+            vec!["muted yellow at 1:46".to_owned()],
+            day.unknown_colors
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec!["unknown color muted yellow at 1:46"],
+            day.diagnostics()
+        );
+        assert_eq!(None, day.puzzle_00());
+        assert_eq!(None, day.puzzle_01());
+
+        day.parse_input(&format!(
+            "{}\nmuted yellow bags contain no other bags.",
+            input
+        ));
+        assert!(day.unknown_colors.is_empty());
+        assert_eq!(Some(2), day.puzzle_00());
+        assert_eq!(Some(0), day.puzzle_01());
+    }
+
+    #[test]
+    fn color_parser_trace() {
+        let (result, log) = with_trace(|| color_parser().parse("dark 2 red bags"));
+        assert_eq!(Err(ParseError::Mismatch("2 red bags")), result);
+        assert_eq!(
+            "\
+color @ \"dark 2 red bags\"
+  color word @ \"dark 2 red bags\"
+  color word ok \"dark \"
+  color word @ \"2 red bags\"
+  color word mismatch at +0: \"2 red bags\"
+color mismatch at +5: \"2 red bags\"
+",
+            log
+        );
+    }
+
+    #[test]
+    fn trailing_text_is_rejected() {
+        let mut day = Day::default();
+        day.parse_input("shiny gold bags contain no other bags. and more");
+        assert_eq!(
+            Some("line 1: unexpected input \" and more\"".to_owned()),
+            day.parse_error
+        );
+        assert_eq!(
+            vec!["line 1: unexpected input \" and more\""],
+            day.diagnostics()
+        );
+        assert_eq!(None, day.puzzle_00());
+
+        day.parse_input("shiny gold bags contain no other bags.\n");
+        assert_eq!(None, day.parse_error);
+        assert_eq!(Some(0), day.puzzle_00());
+    }
+
+    #[test]
+    fn bags_inside_of_themselves() {
+        let mut day = Day::default();
+        day.parse_input(
+            "light red bags contain 1 shiny gold bag, 2 dark red bags.
+dark red bags contain 1 light red bag.
+shiny gold bags contain 3 faded blue bags.
+faded blue bags contain no other bags.",
+        );
+        assert_eq!(Some(2), day.puzzle_00());
+        assert_eq!(Some(3), day.puzzle_01());
+
+        day.parse_input(
+            "shiny gold bags contain 1 light red bag.
+light red bags contain 1 shiny gold bag.",
+        );
+        assert_eq!(Some(2), day.puzzle_00());
+        assert_eq!(None, day.puzzle_01());
+    }
+
+    #[test]
+    fn blank_lines_are_skipped() {
+        let mut day = Day::default();
+        day.parse_input(
+            "
+light red bags contain 1 shiny gold bag.
+
+shiny gold bags contain 2 faded blue bags.
+  \r
+faded blue bags contain no other bags.
+
+",
         );
+        assert_eq!(None, day.parse_error);
+        assert_eq!(Some(1), day.puzzle_00());
+        assert_eq!(Some(2), day.puzzle_01());
     }
 }
@@ -0,0 +1,163 @@
+use crate::common::*;
+
+#[derive(Default)]
+pub struct Day {
+    input: String,
+    parse_error: Option<String>,
+}
+
+impl AocDay for Day {
+    fn parse_input(&mut self, input: &str) {
+        // both precedences accept the same expressions, so one of them is enough to check them
+        match sum_expressions(input, same_precedence_parser()) {
+            Ok(_) => self.input = input.into(),
+            Err(error) => self.parse_error = Some(error.to_string()),
+        }
+    }
+
+    fn puzzle_00(&self) -> Option<AocPuzzleAnswer> {
+        if self.parse_error.is_some() {
+            return None;
+        }
+        sum_expressions(&self.input, same_precedence_parser()).ok()?
+    }
+
+    fn puzzle_01(&self) -> Option<AocPuzzleAnswer> {
+        if self.parse_error.is_some() {
+            return None;
+        }
+        sum_expressions(&self.input, addition_first_parser()).ok()?
+    }
+
+    fn diagnostics(&self) -> Vec<String> {
+        self.parse_error.iter().cloned().collect()
+    }
+}
+
+/// value of an expression, `None` once it doesn't fit
+type Value = Option<AocPuzzleAnswer>;
+
+fn add(a: Value, b: Value) -> Value {
+    a?.checked_add(b?)
+}
+
+fn mul(a: Value, b: Value) -> Value {
+    a?.checked_mul(b?)
+}
+
+/// `+` and `*` evaluated strictly left to right
+fn same_precedence_parser<'a>() -> impl Parser<'a, Value> {
+    precedence(map(unsigned(), Some))
+        .infix("+", 1, Assoc::Left, add)
+        .infix("*", 1, Assoc::Left, mul)
+        .group("(", ")")
+}
+
+/// `+` binding tighter than `*`
+fn addition_first_parser<'a>() -> impl Parser<'a, Value> {
+    precedence(map(unsigned(), Some))
+        .infix("+", 2, Assoc::Left, add)
+        .infix("*", 1, Assoc::Left, mul)
+        .group("(", ")")
+}
+
+/// the first malformed line, otherwise `None` if the sum gets too large,
+/// blank lines and whitespace around the expressions are fine
+fn sum_expressions<'a, P>(input: &'a str, parser: P) -> Result<Value, LineError<&'a str>>
+where
+    P: Parser<'a, Value>,
+{
+    let values = lines_of(left(parser, space_0()))(input)?;
+    Ok(values
+        .into_iter()
+        .try_fold(0 as AocPuzzleAnswer, |sum, value| sum.checked_add(value?)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_precedence_examples() {
+        let parser = same_precedence_parser();
+        let evaluate = |line| parser.parse(line).map(|(_, value)| value.unwrap());
+        assert_eq!(Ok(71), evaluate("1 + 2 * 3 + 4 * 5 + 6"));
+        assert_eq!(Ok(51), evaluate("1 + (2 * 3) + (4 * (5 + 6))"));
+        assert_eq!(Ok(26), evaluate("2 * 3 + (4 * 5)"));
+        assert_eq!(Ok(437), evaluate("5 + (8 * 3 + 9 + 3 * 4 * 3)"));
+        assert_eq!(
+            Ok(13632),
+            evaluate("((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2")
+        );
+    }
+
+    #[test]
+    fn addition_first_examples() {
+        let parser = addition_first_parser();
+        let evaluate = |line| parser.parse(line).map(|(_, value)| value.unwrap());
+        assert_eq!(Ok(231), evaluate("1 + 2 * 3 + 4 * 5 + 6"));
+        assert_eq!(Ok(51), evaluate("1 + (2 * 3) + (4 * (5 + 6))"));
+        assert_eq!(Ok(46), evaluate("2 * 3 + (4 * 5)"));
+        assert_eq!(Ok(1445), evaluate("5 + (8 * 3 + 9 + 3 * 4 * 3)"));
+        assert_eq!(
+            Ok(23340),
+            evaluate("((2 + 4 * 9) * (6 + 9 * 8 + 6) + 6) + 2 + 4 * 2")
+        );
+    }
+
+    #[test]
+    fn sums_all_lines() {
+        let input = "1 + 2 * 3\n2 * 3 + (4 * 5)\n";
+        assert_eq!(
+            Ok(Some(9 + 26)),
+            sum_expressions(input, same_precedence_parser())
+        );
+        assert_eq!(
+            Ok(Some(9 + 46)),
+            sum_expressions(input, addition_first_parser())
+        );
+    }
+
+    #[test]
+    fn malformed_lines_are_reported() {
+        let error = |input| {
+            sum_expressions(input, same_precedence_parser())
+                .unwrap_err()
+                .line
+        };
+        assert_eq!(1, error("1 + (2"));
+        assert_eq!(3, error("1\n\n1 + 2 x"));
+
+        let mut day = Day::default();
+        day.parse_input("1 + 2\n3 *\n");
+        assert_eq!(None, day.puzzle_00());
+        assert_eq!(None, day.puzzle_01());
+        assert_eq!(1, day.diagnostics().len());
+        assert!(day.diagnostics()[0].starts_with("line 2: "));
+    }
+
+    #[test]
+    fn blank_lines_and_whitespace_are_skipped() {
+        let input = "\n 1 + 2 * 3 \t\n\n  \n2 * 3 + (4 * 5)\r\n\n";
+        assert_eq!(
+            Ok(Some(9 + 26)),
+            sum_expressions(input, same_precedence_parser())
+        );
+    }
+
+    #[test]
+    fn overflow_is_none() {
+        let max = AocPuzzleAnswer::MAX;
+        let input = format!("{} + 1", max);
+        assert_eq!(Ok(None), sum_expressions(&input, same_precedence_parser()));
+        let input = format!("{} * 2", max / 2 + 1);
+        assert_eq!(Ok(None), sum_expressions(&input, addition_first_parser()));
+        let input = format!("{}\n1", max);
+        assert_eq!(Ok(None), sum_expressions(&input, addition_first_parser()));
+        let input = format!("{}\n0", max);
+        assert_eq!(
+            Ok(Some(max)),
+            sum_expressions(&input, addition_first_parser())
+        );
+    }
+}
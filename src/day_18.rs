@@ -0,0 +1,152 @@
+use crate::common::*;
+
+#[derive(Default)]
+pub struct Day {
+    grammar: Option<Grammar>,
+    messages: Vec<String>,
+    parse_error: Option<String>,
+}
+
+impl AocDay for Day {
+    fn parse_input(&mut self, input: &str) {
+        let mut blocks = blocks(input).into_iter();
+        match Grammar::from_rules(blocks.next().unwrap_or("")) {
+            Ok(grammar) => self.grammar = Some(grammar),
+            Err(error) => self.parse_error = Some(error.to_string()),
+        }
+        self.messages = blocks
+            .flat_map(str::lines)
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(String::from)
+            .collect();
+    }
+
+    fn puzzle_00(&self) -> Option<AocPuzzleAnswer> {
+        let grammar = self.grammar.as_ref()?;
+        Some(count_matching_messages(grammar, &self.messages))
+    }
+
+    fn puzzle_01(&self) -> Option<AocPuzzleAnswer> {
+        let grammar = with_looping_rules(self.grammar.clone()?);
+        Some(count_matching_messages(&grammar, &self.messages))
+    }
+
+    fn diagnostics(&self) -> Vec<String> {
+        self.parse_error.iter().cloned().collect()
+    }
+}
+
+/// rules 8 and 11 replaced with `8: 42 | 42 8` and `11: 42 31 | 42 11 31`
+fn with_looping_rules(mut grammar: Grammar) -> Grammar {
+    grammar.insert(8, Rule::Alternatives(vec![vec![42], vec![42, 8]]));
+    grammar.insert(11, Rule::Alternatives(vec![vec![42, 31], vec![42, 11, 31]]));
+    grammar
+}
+
+fn count_matching_messages(grammar: &Grammar, messages: &[String]) -> usize {
+    messages
+        .iter()
+        .filter(|message| grammar.matches(0, message))
+        .count()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn simple_rules_example() {
+        let input = r#"0: 4 1 5
+1: 2 3 | 3 2
+2: 4 4 | 5 5
+3: 4 5 | 5 4
+4: "a"
+5: "b"
+
+ababbb
+bababa
+abbbab
+aaabbb
+aaaabbb"#;
+        let mut day = Day::default();
+        day.parse_input(input);
+        assert_eq!(Some(2), day.puzzle_00());
+    }
+
+    #[test]
+    fn looping_rules_example() {
+        let mut day = Day::default();
+        day.parse_input(LOOPING_EXAMPLE);
+        assert_eq!(Some(3), day.puzzle_00());
+        assert_eq!(Some(12), day.puzzle_01());
+    }
+
+    #[test]
+    fn crlf_line_endings() {
+        let mut day = Day::default();
+        day.parse_input(&LOOPING_EXAMPLE.replace('\n', "\r\n"));
+        assert_eq!(Some(3), day.puzzle_00());
+        assert_eq!(Some(12), day.puzzle_01());
+    }
+
+    #[test]
+    fn malformed_rules() {
+        let mut day = Day::default();
+        day.parse_input("0: 1 ?\n\na");
+        assert_eq!(None, day.puzzle_00());
+        assert_eq!(None, day.puzzle_01());
+        assert_eq!(
+            vec!["line 1: unexpected input \"?\"".to_string()],
+            day.diagnostics()
+        );
+    }
+
+    const LOOPING_EXAMPLE: &str = r#"42: 9 14 | 10 1
+9: 14 27 | 1 26
+10: 23 14 | 28 1
+1: "a"
+11: 42 31
+5: 1 14 | 15 1
+19: 14 1 | 14 14
+12: 24 14 | 19 1
+16: 15 1 | 14 14
+31: 14 17 | 1 13
+6: 14 14 | 1 14
+2: 1 24 | 14 4
+0: 8 11
+13: 14 3 | 1 12
+15: 1 | 14
+17: 14 2 | 1 7
+23: 25 1 | 22 14
+28: 16 1
+4: 1 1
+20: 14 14 | 1 15
+3: 5 14 | 16 1
+27: 1 6 | 14 18
+14: "b"
+21: 14 1 | 1 14
+25: 1 1 | 1 14
+22: 14 14
+8: 42
+26: 14 22 | 1 20
+18: 15 15
+7: 14 5 | 1 21
+24: 14 1
+
+abbbbbabbbaaaababbaabbbbabababbbabbbbbbabaaaa
+bbabbbbaabaabba
+babbbbaabbbbbabbbbbbaabaaabaaa
+aaabbbbbbaaaabaababaabababbabaaabbababababaaa
+bbbbbbbaaaabbbbaaabbabaaa
+bbbababbbbaaaaaaaabbababaaababaabab
+ababaaaaaabaaab
+ababaaaaabbbaba
+baabbaaaabbaaaababbaababb
+abbbbabbbbaaaababbbbbbaaaababb
+aaaaabbaabaaaaababaa
+aaaabbaaaabbaaa
+aaaabbaabbaaaaaaabbbabbbaaabbaabaaa
+babaaabbbaaabaababbaabababaaab
+aabbbbbaabbbaaaaaabbbbbababaaaaabbaaabba"#;
+}
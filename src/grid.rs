@@ -0,0 +1,388 @@
+///
+/// rectangular grids of cells shared by the map puzzles
+///
+use crate::parser_combinator::{grid_of, trimmed_grid_of, GridError};
+use std::ops::{Add, Index, IndexMut, Mul};
+
+/// signed offset or position, `x` going right and `y` going down
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct Vec2(pub isize, pub isize);
+
+impl Add for Vec2 {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self(self.0 + rhs.0, self.1 + rhs.1)
+    }
+}
+
+impl Mul<isize> for Vec2 {
+    type Output = Self;
+
+    fn mul(self, rhs: isize) -> Self::Output {
+        Self(self.0 * rhs, self.1 * rhs)
+    }
+}
+
+/// anything a grid can be indexed with, `(usize, usize)` or a possibly negative `Vec2`
+pub trait Coordinate: Copy {
+    /// `(x, y)` if it's inside of a `width` by `height` grid
+    fn in_grid(self, width: usize, height: usize) -> Option<(usize, usize)>;
+}
+
+impl Coordinate for (usize, usize) {
+    fn in_grid(self, width: usize, height: usize) -> Option<(usize, usize)> {
+        if self.0 < width && self.1 < height {
+            Some(self)
+        } else {
+            None
+        }
+    }
+}
+
+impl Coordinate for Vec2 {
+    fn in_grid(self, width: usize, height: usize) -> Option<(usize, usize)> {
+        if self.0 < 0 || self.1 < 0 {
+            return None;
+        }
+        (self.0 as usize, self.1 as usize).in_grid(width, height)
+    }
+}
+
+const NEIGHBORS_4: [Vec2; 4] = [Vec2(0, -1), Vec2(1, 0), Vec2(0, 1), Vec2(-1, 0)];
+const NEIGHBORS_8: [Vec2; 8] = [
+    Vec2(-1, -1),
+    Vec2(0, -1),
+    Vec2(1, -1),
+    Vec2(1, 0),
+    Vec2(1, 1),
+    Vec2(0, 1),
+    Vec2(-1, 1),
+    Vec2(-1, 0),
+];
+
+/// cells stored row after row in a single `Vec`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid<T> {
+    width: usize,
+    height: usize,
+    cells: Vec<T>,
+}
+
+/// empty grid, derived `Default` would needlessly ask for `T: Default`
+impl<T> Default for Grid<T> {
+    fn default() -> Self {
+        Self {
+            width: 0,
+            height: 0,
+            cells: Vec::new(),
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    /// rows of `width` cells, `None` unless the cells fill up the last row
+    pub fn from_cells(width: usize, cells: Vec<T>) -> Option<Self> {
+        match width {
+            0 if cells.is_empty() => Some(Self::default()),
+            0 => None,
+            _ => {
+                let height = cells.len() / width;
+                if height * width == cells.len() {
+                    Some(Self {
+                        width,
+                        height,
+                        cells,
+                    })
+                } else {
+                    None
+                }
+            }
+        }
+    }
+
+    /// rows one after another, `None` unless they are all equally wide
+    pub fn from_rows(rows: Vec<Vec<T>>) -> Option<Self> {
+        let width = rows.first().map_or(0, Vec::len);
+        if rows.iter().any(|row| row.len() != width) {
+            return None;
+        }
+        Self::from_cells(width, rows.into_iter().flatten().collect())
+    }
+
+    /// every character of every line mapped through `cell`, see `grid_of`
+    pub fn parse<E, F>(input: &str, cell: F) -> Result<Self, GridError<E>>
+    where
+        F: Fn(char) -> Result<T, E>,
+    {
+        let rows = grid_of(cell)(input)?;
+        Ok(Self::from_rows(rows).expect("grid_of rows are equally wide"))
+    }
+
+    /// like `parse`, but lines are trimmed and blank ones skipped, see `trimmed_grid_of`
+    pub fn parse_trimmed<E, F>(input: &str, cell: F) -> Result<Self, GridError<E>>
+    where
+        F: Fn(char) -> Result<T, E>,
+    {
+        let rows = trimmed_grid_of(cell)(input)?;
+        Ok(Self::from_rows(rows).expect("trimmed_grid_of rows are equally wide"))
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    fn offset<C: Coordinate>(&self, at: C) -> Option<usize> {
+        at.in_grid(self.width, self.height)
+            .map(|(x, y)| y * self.width + x)
+    }
+
+    pub fn get<C: Coordinate>(&self, at: C) -> Option<&T> {
+        self.offset(at).map(|i| &self.cells[i])
+    }
+
+    pub fn get_mut<C: Coordinate>(&mut self, at: C) -> Option<&mut T> {
+        self.offset(at).map(move |i| &mut self.cells[i])
+    }
+
+    /// all positions, row after row
+    pub fn positions(&self) -> impl Iterator<Item = (usize, usize)> {
+        let width = self.width;
+        (0..self.height).flat_map(move |y| (0..width).map(move |x| (x, y)))
+    }
+
+    /// cells along with their positions, row after row
+    pub fn iter(&self) -> impl Iterator<Item = ((usize, usize), &T)> {
+        self.positions().zip(self.cells.iter())
+    }
+
+    pub fn row(&self, y: usize) -> Option<&[T]> {
+        if y < self.height {
+            Some(&self.cells[y * self.width..(y + 1) * self.width])
+        } else {
+            None
+        }
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &[T]> {
+        // chunks panics on 0, there are no rows to go over then anyway
+        self.cells.chunks(self.width.max(1))
+    }
+
+    pub fn column(&self, x: usize) -> impl Iterator<Item = &T> {
+        let cells = if x < self.width {
+            &self.cells[x..]
+        } else {
+            &[]
+        };
+        cells.iter().step_by(self.width.max(1))
+    }
+
+    pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = &T>> {
+        (0..self.width).map(move |x| self.column(x))
+    }
+
+    /// positions next to `at` inside of the grid, up, right, down and left
+    pub fn neighbors_4(&self, at: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.neighbors(at, &NEIGHBORS_4)
+    }
+
+    /// positions next to `at` inside of the grid, diagonals included, clockwise from top left
+    pub fn neighbors_8(&self, at: (usize, usize)) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.neighbors(at, &NEIGHBORS_8)
+    }
+
+    fn neighbors<'g>(
+        &'g self,
+        at: (usize, usize),
+        offsets: &'static [Vec2],
+    ) -> impl Iterator<Item = (usize, usize)> + 'g {
+        let at = Vec2(at.0 as isize, at.1 as isize);
+        offsets
+            .iter()
+            .filter_map(move |&offset| (at + offset).in_grid(self.width, self.height))
+    }
+
+    /// new grid with cells at `(x, y)` taken from `source(x, y)` of this one
+    fn remap<F>(&self, width: usize, height: usize, source: F) -> Self
+    where
+        T: Clone,
+        F: Fn(usize, usize) -> (usize, usize),
+    {
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| self[source(x, y)].clone())
+            .collect();
+        Self {
+            width,
+            height,
+            cells,
+        }
+    }
+
+    pub fn rotate_clockwise(&self) -> Self
+    where
+        T: Clone,
+    {
+        let height = self.height;
+        self.remap(self.height, self.width, |x, y| (y, height - 1 - x))
+    }
+
+    pub fn rotate_counterclockwise(&self) -> Self
+    where
+        T: Clone,
+    {
+        let width = self.width;
+        self.remap(self.height, self.width, |x, y| (width - 1 - y, x))
+    }
+
+    /// mirrored left to right
+    pub fn flip_horizontal(&self) -> Self
+    where
+        T: Clone,
+    {
+        let width = self.width;
+        self.remap(self.width, self.height, |x, y| (width - 1 - x, y))
+    }
+
+    /// mirrored top to bottom
+    pub fn flip_vertical(&self) -> Self
+    where
+        T: Clone,
+    {
+        let height = self.height;
+        self.remap(self.width, self.height, |x, y| (x, height - 1 - y))
+    }
+}
+
+impl<T, C: Coordinate + std::fmt::Debug> Index<C> for Grid<T> {
+    type Output = T;
+
+    fn index(&self, at: C) -> &Self::Output {
+        let (width, height) = (self.width, self.height);
+        self.get(at)
+            .unwrap_or_else(|| panic!("{:?} is outside of the {}x{} grid", at, width, height))
+    }
+}
+
+impl<T, C: Coordinate + std::fmt::Debug> IndexMut<C> for Grid<T> {
+    fn index_mut(&mut self, at: C) -> &mut Self::Output {
+        let (width, height) = (self.width, self.height);
+        self.get_mut(at)
+            .unwrap_or_else(|| panic!("{:?} is outside of the {}x{} grid", at, width, height))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digits(input: &str) -> Grid<u32> {
+        Grid::parse(input, |c| c.to_digit(10).ok_or(())).unwrap()
+    }
+
+    #[test]
+    fn access() {
+        let mut grid = digits("123\n456");
+        assert_eq!((3, 2), (grid.width(), grid.height()));
+        assert_eq!(Some(&6), grid.get((2, 1)));
+        assert_eq!(Some(&4), grid.get(Vec2(0, 1)));
+        assert_eq!(None, grid.get((3, 0)));
+        assert_eq!(None, grid.get(Vec2(-1, 0)));
+        assert_eq!(2, grid[Vec2(1, 0)]);
+
+        grid[(1, 1)] = 0;
+        assert_eq!(digits("123\n406"), grid);
+        assert_eq!(None, grid.get_mut(Vec2(0, 2)));
+
+        assert_eq!(
+            Some(grid.clone()),
+            Grid::from_cells(3, vec![1, 2, 3, 4, 0, 6])
+        );
+        assert_eq!(None, Grid::from_cells(4, vec![1, 2, 3, 4, 0, 6]));
+        assert_eq!(Some(Grid::default()), Grid::<u32>::from_cells(0, vec![]));
+        assert_eq!(
+            Some(grid.clone()),
+            Grid::from_rows(vec![vec![1, 2, 3], vec![4, 0, 6]])
+        );
+        assert_eq!(None, Grid::from_rows(vec![vec![1, 2, 3], vec![4, 0]]));
+        assert_eq!(Some(Grid::default()), Grid::<u32>::from_rows(vec![]));
+        assert_eq!(
+            Ok(grid),
+            Grid::parse_trimmed(" 123\n\n 406 \n", |c| c.to_digit(10).ok_or(()))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Vec2(0, -1) is outside of the 3x2 grid")]
+    fn index_out_of_bounds() {
+        let grid = digits("123\n456");
+        let _ = grid[Vec2(0, -1)];
+    }
+
+    #[test]
+    fn rows_columns_and_positions() {
+        let grid = digits("123\n456");
+        assert_eq!(
+            vec![&[1, 2, 3][..], &[4, 5, 6]],
+            grid.rows().collect::<Vec<_>>()
+        );
+        assert_eq!(Some(&[4, 5, 6][..]), grid.row(1));
+        assert_eq!(None, grid.row(2));
+        assert_eq!(vec![&2, &5], grid.column(1).collect::<Vec<_>>());
+        assert_eq!(0, grid.column(3).count());
+        assert_eq!(
+            vec![vec![1, 4], vec![2, 5], vec![3, 6]],
+            grid.columns()
+                .map(|column| column.copied().collect::<Vec<_>>())
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![((0, 0), &1), ((1, 0), &2), ((2, 0), &3), ((0, 1), &4)],
+            grid.iter().take(4).collect::<Vec<_>>()
+        );
+        assert_eq!(0, Grid::<u32>::default().rows().count());
+    }
+
+    #[test]
+    fn neighbors() {
+        let grid = digits("123\n456\n789");
+        assert_eq!(
+            vec![(1, 0), (2, 1), (1, 2), (0, 1)],
+            grid.neighbors_4((1, 1)).collect::<Vec<_>>()
+        );
+        assert_eq!(8, grid.neighbors_8((1, 1)).count());
+        assert_eq!(
+            vec![(1, 0), (1, 1), (0, 1)],
+            grid.neighbors_8((0, 0)).collect::<Vec<_>>()
+        );
+        assert_eq!(
+            vec![(2, 1), (1, 2)],
+            grid.neighbors_4((2, 2)).collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn rotations_and_flips() {
+        let grid = digits("123\n456");
+        assert_eq!(digits("41\n52\n63"), grid.rotate_clockwise());
+        assert_eq!(digits("36\n25\n14"), grid.rotate_counterclockwise());
+        assert_eq!(digits("321\n654"), grid.flip_horizontal());
+        assert_eq!(digits("456\n123"), grid.flip_vertical());
+        assert_eq!(
+            grid,
+            grid.rotate_clockwise()
+                .rotate_clockwise()
+                .rotate_clockwise()
+                .rotate_clockwise()
+        );
+        assert_eq!(
+            grid.rotate_counterclockwise(),
+            grid.rotate_clockwise().flip_horizontal().flip_vertical()
+        );
+    }
+}
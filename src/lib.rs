@@ -0,0 +1,23 @@
+#![feature(str_split_once)]
+#![cfg_attr(test, feature(test))]
+
+#[macro_use]
+extern crate bitflags;
+#[cfg(test)]
+extern crate test;
+
+pub mod common;
+pub mod day_00;
+pub mod day_01;
+pub mod day_02;
+pub mod day_03;
+pub mod day_04;
+pub mod day_05;
+pub mod day_06;
+pub mod day_07;
+pub mod day_08;
+pub mod day_17;
+pub mod day_18;
+pub mod grid;
+pub mod parser_combinator;
+pub mod setup;
@@ -1,28 +1,18 @@
-#![feature(str_split_once)]
-
-#[macro_use]
-extern crate bitflags;
-
-use crate::common::*;
-use crate::setup::PuzzleDayInfo;
-
-mod common;
-mod day_00;
-mod day_01;
-mod day_02;
-mod day_03;
-mod day_04;
-mod day_05;
-mod day_06;
-mod day_07;
-mod day_08;
-mod parser_combinator;
-mod setup;
+use aoc_2020::common::*;
+use aoc_2020::setup::{self, PuzzleDayInfo};
 
 fn main() {
     let mut days = setup::setup_puzzle_day_info_vec();
 
-    for (i, day_info) in days.iter_mut().enumerate() {
+    for arg in std::env::args().skip(1) {
+        if let Err(error) = apply_option(&mut days, &arg) {
+            eprintln!("{}: {}", arg, error);
+            eprintln!("usage: aoc_2020 [day_NN.option=value]...");
+            std::process::exit(1);
+        }
+    }
+
+    for day_info in days.iter_mut() {
         let (puzzle_00, puzzle_01) = get_answers(day_info);
 
         let print_puzzle_result = |result, i| match result {
@@ -30,14 +20,46 @@ fn main() {
             None => println!("\t{}. UNIMPLEMENTED", i),
         };
 
-        println!("day_{:02}", i);
+        println!("day_{:02}", day_info.0);
         print_puzzle_result(puzzle_00, 0);
         print_puzzle_result(puzzle_01, 1);
+        print_diagnostics(&day_info.1.diagnostics());
+    }
+}
+
+const MAX_DIAGNOSTICS: usize = 5;
+
+fn print_diagnostics(diagnostics: &[String]) {
+    if diagnostics.is_empty() {
+        return;
+    }
+
+    println!("\t! {} input problem(s):", diagnostics.len());
+    for diagnostic in diagnostics.iter().take(MAX_DIAGNOSTICS) {
+        println!("\t\t{}", diagnostic);
     }
+    if diagnostics.len() > MAX_DIAGNOSTICS {
+        println!("\t\t... and {} more", diagnostics.len() - MAX_DIAGNOSTICS);
+    }
+}
+
+/// `day_NN.name=value` passed on to the day numbered `NN`
+fn apply_option(days: &mut [PuzzleDayInfo], arg: &str) -> Result<(), String> {
+    let (key, value) = arg.split_once('=').ok_or("expected day_NN.option=value")?;
+    let (day, name) = key.split_once('.').ok_or("expected day_NN.option=value")?;
+    let number = day
+        .strip_prefix("day_")
+        .and_then(|number| number.parse::<usize>().ok())
+        .ok_or_else(|| format!("{:?} isn't a day", day))?;
+    let PuzzleDayInfo(_, day, _) = days
+        .iter_mut()
+        .find(|day_info| day_info.0 == number)
+        .ok_or_else(|| format!("no day_{:02}", number))?;
+    day.set_option(name, value)
 }
 
 fn get_answers(day_info: &mut PuzzleDayInfo) -> (Option<AocPuzzleAnswer>, Option<AocPuzzleAnswer>) {
-    let PuzzleDayInfo(day, input) = day_info;
+    let PuzzleDayInfo(_, day, input) = day_info;
     day.parse_input(input);
     let puzzle_00 = day.puzzle_00();
     let puzzle_01 = day.puzzle_01();
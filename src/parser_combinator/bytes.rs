@@ -0,0 +1,114 @@
+///
+/// byte-oriented counterparts of the `&str` parsers,
+/// faster to run over all-ASCII inputs since there is no UTF-8 decoding involved
+///
+use super::*;
+
+#[allow(dead_code)]
+pub fn any_byte<'a>() -> impl Parser<'a, u8, &'a [u8]> {
+    any_item()
+}
+
+#[allow(dead_code)]
+pub fn byte<'a>(expected: u8) -> impl Parser<'a, u8, &'a [u8]> {
+    pred(any_byte(), move |&b| b == expected)
+}
+
+#[allow(dead_code)]
+pub fn tag<'a>(expected: &'static [u8]) -> impl Parser<'a, (), &'a [u8]> {
+    move |input: &'a [u8]| {
+        if input.starts_with(expected) {
+            Ok((&input[expected.len()..], ()))
+        } else {
+            Err(ParseError::Mismatch(input))
+        }
+    }
+}
+
+/// single ASCII digit converted to its value
+#[allow(dead_code)]
+pub fn digit<'a>() -> impl Parser<'a, u8, &'a [u8]> {
+    map(pred(any_byte(), u8::is_ascii_digit), |b| b - b'0')
+}
+
+#[allow(dead_code)]
+pub fn digits<'a>() -> impl Parser<'a, &'a [u8], &'a [u8]> {
+    take_while1(|b: u8| b.is_ascii_digit())
+}
+
+#[allow(dead_code)]
+pub fn space_0<'a>() -> impl Parser<'a, &'a [u8], &'a [u8]> {
+    take_while(|b: u8| b.is_ascii_whitespace())
+}
+
+#[allow(dead_code)]
+pub fn space_1<'a>() -> impl Parser<'a, &'a [u8], &'a [u8]> {
+    take_while1(|b: u8| b.is_ascii_whitespace())
+}
+
+#[allow(dead_code)]
+pub fn unsigned<'a, T: Integer>() -> impl Parser<'a, T, &'a [u8]> {
+    move |input: &'a [u8]| {
+        let (tail, digits) = digits().parse(input)?;
+        // digits are always ASCII, so this can't fail
+        let digits = std::str::from_utf8(digits).unwrap();
+        match T::from_str_radix(digits, 10) {
+            Ok(value) => Ok((tail, value)),
+            Err(_) => Err(ParseError::Overflow(input)),
+        }
+    }
+}
+
+#[allow(dead_code)]
+pub fn number<'a>() -> impl Parser<'a, u32, &'a [u8]> {
+    unsigned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn byte_parser() {
+        assert_eq!(Ok((&b"bc"[..], b'a')), byte(b'a').parse(b"abc"));
+        assert_eq!(
+            Err(ParseError::Mismatch(&b"cba"[..])),
+            byte(b'a').parse(b"cba")
+        );
+    }
+
+    #[test]
+    fn tag_parser() {
+        let parser = tag(b"bags");
+        assert_eq!(Ok((&b" contain"[..], ())), parser.parse(b"bags contain"));
+        assert_eq!(Err(ParseError::Mismatch(&b"bag"[..])), parser.parse(b"bag"));
+    }
+
+    #[test]
+    fn digit_parsers() {
+        assert_eq!(Ok((&b"2"[..], 1)), digit().parse(b"12"));
+        assert_eq!(Ok((&b"x"[..], 1721)), number().parse(b"1721x"));
+        assert_eq!(
+            Err(ParseError::Overflow(&b"256"[..])),
+            unsigned::<u8>().parse(b"256")
+        );
+        assert_eq!(Err(ParseError::Mismatch(&b"-1"[..])), number().parse(b"-1"));
+    }
+
+    #[test]
+    fn generic_combinators_over_bytes() {
+        let parser = one_or_more(left(number(), space_0()));
+        assert_eq!(
+            Ok((&b""[..], vec![1721, 979, 366])),
+            parser.parse(b"1721\n979\n366\n")
+        );
+        assert_eq!(
+            Ok((&b" 2"[..], &b"1 1"[..])),
+            recognize(pair(number(), pair(space_1(), number()))).parse(b"1 1 2")
+        );
+        assert_eq!(
+            Ok((&b"#"[..], vec![b'.', b'.'])),
+            zero_or_more(either(byte(b'.'), byte(b','))).parse(b"..#")
+        );
+    }
+}
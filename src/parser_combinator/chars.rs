@@ -0,0 +1,155 @@
+///
+/// character classes and the parsers built on top of them
+///
+use super::*;
+use std::ops::RangeInclusive;
+
+/// set of characters, `'a'..='f'`, `"+-"` and `'x'` are all classes,
+/// tuples of classes match any of their members
+pub trait CharClass {
+    fn contains_char(&self, c: char) -> bool;
+}
+
+impl CharClass for char {
+    fn contains_char(&self, c: char) -> bool {
+        *self == c
+    }
+}
+
+impl CharClass for &str {
+    fn contains_char(&self, c: char) -> bool {
+        self.contains(c)
+    }
+}
+
+impl CharClass for RangeInclusive<char> {
+    fn contains_char(&self, c: char) -> bool {
+        self.contains(&c)
+    }
+}
+
+macro_rules! impl_char_class_for_tuples {
+    ($(($($name:ident $index:tt),+))*) => {
+        $(
+            impl<$($name: CharClass),+> CharClass for ($($name,)+) {
+                fn contains_char(&self, c: char) -> bool {
+                    $(self.$index.contains_char(c))||+
+                }
+            }
+        )*
+    };
+}
+
+impl_char_class_for_tuples! {
+    (A 0, B 1)
+    (A 0, B 1, C 2)
+    (A 0, B 1, C 2, D 3)
+}
+
+/// single character belonging to `class`
+#[allow(dead_code)]
+pub fn one_of<'a, C: CharClass>(class: C) -> impl Parser<'a, char> {
+    pred(any_char(), move |&c| class.contains_char(c))
+}
+
+/// single character outside of `class`
+#[allow(dead_code)]
+pub fn none_of<'a, C: CharClass>(class: C) -> impl Parser<'a, char> {
+    pred(any_char(), move |&c| !class.contains_char(c))
+}
+
+/// longest non-empty run of characters belonging to `class`
+#[allow(dead_code)]
+pub fn class1<'a, C: CharClass>(class: C) -> impl Parser<'a, &'a str> {
+    take_while1(move |c| class.contains_char(c))
+}
+
+#[allow(dead_code)]
+pub fn alpha1<'a>() -> impl Parser<'a, &'a str> {
+    take_while1(|c: char| c.is_ascii_alphabetic())
+}
+
+#[allow(dead_code)]
+pub fn digit1<'a>() -> impl Parser<'a, &'a str> {
+    take_while1(|c: char| c.is_ascii_digit())
+}
+
+#[allow(dead_code)]
+pub fn hex_digit1<'a>() -> impl Parser<'a, &'a str> {
+    take_while1(|c: char| c.is_ascii_hexdigit())
+}
+
+#[allow(dead_code)]
+pub fn alphanumeric1<'a>() -> impl Parser<'a, &'a str> {
+    take_while1(|c: char| c.is_ascii_alphanumeric())
+}
+
+/// `\n` or `\r\n`
+#[allow(dead_code)]
+pub fn line_ending<'a>() -> impl Parser<'a, &'a str> {
+    either(
+        recognize(match_literal("\n")),
+        recognize(match_literal("\r\n")),
+    )
+}
+
+/// `expected` ignoring ASCII case, returns the slice as it appears in the input
+#[allow(dead_code)]
+pub fn tag_no_case<'a>(expected: &'static str) -> impl Parser<'a, &'a str> {
+    move |input: &'a str| match input.get(..expected.len()) {
+        Some(head) if head.eq_ignore_ascii_case(expected) => Ok((&input[expected.len()..], head)),
+        _ => Err(ParseError::Mismatch(input)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn one_of_none_of() {
+        assert_eq!(Ok(("bc", 'a')), one_of("abc").parse("abc"));
+        assert_eq!(Err(ParseError::Mismatch("x")), one_of("abc").parse("x"));
+        assert_eq!(Err(ParseError::Mismatch("")), one_of("abc").parse(""));
+        assert_eq!(Ok(("", 'e')), one_of('a'..='f').parse("e"));
+        assert_eq!(Ok(("", '-')), one_of(('0'..='9', '-')).parse("-"));
+
+        assert_eq!(Ok(("a", 'x')), none_of("abc").parse("xa"));
+        assert_eq!(Err(ParseError::Mismatch("a")), none_of("abc").parse("a"));
+        assert_eq!(Err(ParseError::Mismatch("")), none_of("abc").parse(""));
+    }
+
+    #[test]
+    fn char_classes() {
+        let lower_hex = class1(('0'..='9', 'a'..='f'));
+        assert_eq!(Ok(("G", "09af")), lower_hex.parse("09afG"));
+        assert_eq!(Err(ParseError::Mismatch("A")), lower_hex.parse("A"));
+
+        assert_eq!(Ok(("1", "abC")), alpha1().parse("abC1"));
+        assert_eq!(Ok(("x", "0123")), digit1().parse("0123x"));
+        assert_eq!(Ok(("g", "09aF")), hex_digit1().parse("09aFg"));
+        assert_eq!(Ok(("-", "a1B2")), alphanumeric1().parse("a1B2-"));
+        assert_eq!(Err(ParseError::Mismatch("é")), alpha1().parse("é"));
+        assert_eq!(Err(ParseError::Mismatch("")), digit1().parse(""));
+    }
+
+    #[test]
+    fn line_ending_parser() {
+        assert_eq!(Ok(("x", "\n")), line_ending().parse("\nx"));
+        assert_eq!(Ok(("x", "\r\n")), line_ending().parse("\r\nx"));
+        assert_eq!(Err(ParseError::Mismatch("\rx")), line_ending().parse("\rx"));
+    }
+
+    #[test]
+    fn tag_no_case_parser() {
+        assert_eq!(Ok((" x", "HeLLo")), tag_no_case("hello").parse("HeLLo x"));
+        assert_eq!(
+            Err(ParseError::Mismatch("help")),
+            tag_no_case("hello").parse("help")
+        );
+        assert_eq!(
+            Err(ParseError::Mismatch("hé")),
+            tag_no_case("hel").parse("hé")
+        );
+    }
+}
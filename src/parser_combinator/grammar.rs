@@ -0,0 +1,256 @@
+///
+/// grammars built at runtime from numbered rule tables like
+/// `0: 4 1 5`, `1: 2 3 | 3 2` and `4: "a"`
+///
+use super::*;
+use std::collections::{HashMap, HashSet};
+
+pub type RuleId = usize;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Rule {
+    Literal(String),
+    /// any of the sequences of other rules
+    Alternatives(Vec<Vec<RuleId>>),
+}
+
+/// rules may reference each other recursively, derivations going around a loop without
+/// consuming anything are cut off instead of expanded forever, so left recursive
+/// alternatives never match and have to be written right recursive
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Grammar {
+    rules: HashMap<RuleId, Rule>,
+}
+
+#[allow(dead_code)]
+pub fn rule_parser<'a>() -> impl Parser<'a, (RuleId, Rule)> {
+    let sequence = || sep_by1(unsigned(), match_literal(" "));
+    pair(
+        left(unsigned(), pair(match_literal(":"), space_0())),
+        either(
+            map(quoted_string(), Rule::Literal),
+            map(
+                sep_by1(sequence(), match_literal(" | ")),
+                Rule::Alternatives,
+            ),
+        ),
+    )
+}
+
+#[allow(dead_code)]
+impl Grammar {
+    /// one rule per line, empty lines are skipped
+    pub fn from_rules(input: &str) -> Result<Self, LineError<&str>> {
+        let rules = lines_of(right(space_0(), left(rule_parser(), space_0())))(input)?;
+        Ok(Self {
+            rules: rules.into_iter().collect(),
+        })
+    }
+
+    pub fn insert(&mut self, id: RuleId, rule: Rule) {
+        self.rules.insert(id, rule);
+    }
+
+    pub fn get(&self, id: RuleId) -> Option<&Rule> {
+        self.rules.get(&id)
+    }
+
+    /// whether the whole message can be derived from the rule
+    pub fn matches(&self, id: RuleId, message: &str) -> bool {
+        Matcher::new(self)
+            .match_rule(id, message)
+            .iter()
+            .any(|tail| tail.is_empty())
+    }
+
+    /// parser consuming the longest prefix of the input that the rule derives
+    pub fn parser<'a, 'g>(&'g self, id: RuleId) -> impl Parser<'a, &'a str> + 'g {
+        move |input: &'a str| {
+            let tail = Matcher::new(self)
+                .match_rule(id, input)
+                .into_iter()
+                .min_by_key(|tail| tail.len())
+                .ok_or(ParseError::Mismatch(input))?;
+            Ok((tail, &input[..input.len() - tail.len()]))
+        }
+    }
+}
+
+/// rule and remaining input length, i.e. position in the input being matched
+type Expansion = (RuleId, usize);
+
+/// state of matching one input against a grammar
+struct Matcher<'g, 'a> {
+    grammar: &'g Grammar,
+    /// rules being expanded at a position, getting back to one of them
+    /// means going around in a loop that never consumes anything
+    active: HashSet<Expansion>,
+    /// remainders of expansions that didn't run into such a loop,
+    /// the ones that did depend on what's being expanded around them
+    memo: HashMap<Expansion, Vec<&'a str>>,
+    cuts: usize,
+}
+
+impl<'g, 'a> Matcher<'g, 'a> {
+    fn new(grammar: &'g Grammar) -> Self {
+        Self {
+            grammar,
+            active: HashSet::new(),
+            memo: HashMap::new(),
+            cuts: 0,
+        }
+    }
+
+    /// every possible remainder of the input after matching the rule, longest first;
+    /// rules can be ambiguous, so unlike PEG alternatives all of them are kept
+    fn match_rule(&mut self, id: RuleId, input: &'a str) -> Vec<&'a str> {
+        let key = (id, input.len());
+        if let Some(tails) = self.memo.get(&key) {
+            return tails.clone();
+        }
+        if !self.active.insert(key) {
+            self.cuts += 1;
+            return Vec::new();
+        }
+
+        let cuts = self.cuts;
+        let mut tails = match self.grammar.rules.get(&id) {
+            Some(Rule::Literal(literal)) => {
+                input.strip_prefix(literal.as_str()).into_iter().collect()
+            }
+            Some(Rule::Alternatives(alternatives)) => {
+                let mut tails = Vec::new();
+                for sequence in alternatives {
+                    tails.extend(self.match_sequence(sequence, input));
+                }
+                tails
+            }
+            None => Vec::new(),
+        };
+        dedup(&mut tails);
+
+        self.active.remove(&key);
+        if self.cuts == cuts {
+            self.memo.insert(key, tails.clone());
+        }
+        tails
+    }
+
+    fn match_sequence(&mut self, sequence: &[RuleId], input: &'a str) -> Vec<&'a str> {
+        let mut tails = vec![input];
+        for &id in sequence {
+            let mut next = Vec::new();
+            for tail in tails {
+                next.extend(self.match_rule(id, tail));
+            }
+            dedup(&mut next);
+            tails = next;
+        }
+        tails
+    }
+}
+
+/// remainders are all suffixes of the same input, so they're told apart by length
+fn dedup(tails: &mut Vec<&str>) {
+    tails.sort_unstable_by_key(|tail| std::cmp::Reverse(tail.len()));
+    tails.dedup_by_key(|tail| tail.len());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const RULES: &str = r#"
+0: 4 1 5
+1: 2 3 | 3 2
+2: 4 4 | 5 5
+3: 4 5 | 5 4
+4: "a"
+5: "b"
+"#;
+
+    #[test]
+    fn rule_parsing() {
+        assert_eq!(
+            Ok(("", (4, Rule::Literal("a".into())))),
+            rule_parser().parse("4: \"a\"")
+        );
+        assert_eq!(
+            Ok(("", (1, Rule::Alternatives(vec![vec![2, 3], vec![3, 2]])))),
+            rule_parser().parse("1: 2 3 | 3 2")
+        );
+        assert_eq!(
+            Ok(("", (15, Rule::Alternatives(vec![vec![1], vec![14]])))),
+            rule_parser().parse("15: 1 | 14")
+        );
+        assert_eq!(
+            Err(LineError {
+                line: 2,
+                error: ParseError::Mismatch("x")
+            }),
+            Grammar::from_rules("0: 1 2\nx")
+        );
+    }
+
+    #[test]
+    fn matches_whole_messages() {
+        let grammar = Grammar::from_rules(RULES).unwrap();
+        assert!(grammar.matches(0, "ababbb"));
+        assert!(grammar.matches(0, "abbbab"));
+        assert!(!grammar.matches(0, "bababa"));
+        assert!(!grammar.matches(0, "aaabbb"));
+        assert!(!grammar.matches(0, "aaaabbb"));
+        assert!(!grammar.matches(6, "a"));
+    }
+
+    #[test]
+    fn recursive_rules() {
+        let mut grammar = Grammar::from_rules("0: 8 2\n1: \"a\"\n2: \"b\"").unwrap();
+        grammar.insert(8, Rule::Alternatives(vec![vec![1], vec![1, 8]]));
+        assert!(grammar.matches(0, "ab"));
+        assert!(grammar.matches(0, "aaaab"));
+        assert!(!grammar.matches(0, "b"));
+        assert!(!grammar.matches(0, "aaba"));
+    }
+
+    #[test]
+    fn grammar_parser() {
+        let grammar = Grammar::from_rules(RULES).unwrap();
+        assert_eq!(Ok(("b", "ababbb")), grammar.parser(0).parse("ababbbb"));
+        assert_eq!(
+            Err(ParseError::Mismatch("bababa")),
+            grammar.parser(0).parse("bababa")
+        );
+    }
+
+    #[test]
+    fn loops_without_progress_are_cut() {
+        let grammar = Grammar::from_rules("0: 0").unwrap();
+        assert!(!grammar.matches(0, "a"));
+
+        let grammar = Grammar::from_rules("0: 1\n1: 0").unwrap();
+        assert!(!grammar.matches(0, ""));
+
+        let grammar = Grammar::from_rules("0: 0 1 | 1\n1: \"a\"").unwrap();
+        // the left recursive alternative is cut, only the one after it is left
+        assert!(grammar.matches(0, "a"));
+        assert!(!grammar.matches(0, "aaa"));
+        assert_eq!(Ok(("ab", "a")), grammar.parser(0).parse("aab"));
+
+        let grammar = Grammar::from_rules("0: 1 0 | 2\n1: \"\"\n2: \"a\"").unwrap();
+        assert!(grammar.matches(0, "a"));
+        assert!(!grammar.matches(0, "aa"));
+    }
+
+    #[test]
+    fn ambiguity_stays_polynomial() {
+        // three ways to match every `a`, 3^60 derivations of the whole message
+        let rules = format!(
+            "0: {}\n1: 2 | 2 | 3\n2: \"a\"\n3: 2 4\n4: \"\"",
+            vec!["1"; 60].join(" ")
+        );
+        let grammar = Grammar::from_rules(&rules).unwrap();
+        assert!(grammar.matches(0, &"a".repeat(60)));
+        assert!(!grammar.matches(0, &"a".repeat(59)));
+    }
+}
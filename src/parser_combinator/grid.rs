@@ -0,0 +1,191 @@
+///
+/// rectangular character maps parsed into rows of typed cells,
+/// `crate::grid::Grid` is built from those
+///
+use std::fmt;
+
+/// why a grid couldn't be read, lines and columns are 1-based
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum GridError<E> {
+    /// no rows at all
+    Empty,
+    /// row that doesn't have as many cells as the first one
+    Ragged {
+        line: usize,
+        width: usize,
+        expected: usize,
+    },
+    /// symbol rejected by the cell mapper
+    Cell {
+        line: usize,
+        column: usize,
+        symbol: char,
+        error: E,
+    },
+}
+
+impl<E: fmt::Display> fmt::Display for GridError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GridError::Empty => write!(f, "empty grid"),
+            GridError::Ragged {
+                line,
+                width,
+                expected,
+            } => write!(
+                f,
+                "line {}: {} cells wide, expected {}",
+                line, width, expected
+            ),
+            GridError::Cell {
+                line,
+                column,
+                symbol,
+                error,
+            } => write!(f, "line {}:{}: {:?} {}", line, column, symbol, error),
+        }
+    }
+}
+
+/// every character of every line mapped through `cell`, all lines must be equally wide
+/// and there has to be at least one cell
+#[allow(dead_code)]
+pub fn grid_of<T, E, F>(cell: F) -> impl Fn(&str) -> Result<Vec<Vec<T>>, GridError<E>>
+where
+    F: Fn(char) -> Result<T, E>,
+{
+    move |input: &str| {
+        let rows = input.lines().enumerate().map(|(y, line)| (y + 1, 0, line));
+        read_grid(rows, &cell)
+    }
+}
+
+/// like `grid_of`, but whitespace around the lines and blank lines are skipped,
+/// errors still point at lines and columns of the untrimmed input
+#[allow(dead_code)]
+pub fn trimmed_grid_of<T, E, F>(cell: F) -> impl Fn(&str) -> Result<Vec<Vec<T>>, GridError<E>>
+where
+    F: Fn(char) -> Result<T, E>,
+{
+    move |input: &str| {
+        let rows = input
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(y, line)| {
+                let indent = line.len() - line.trim_start().len();
+                (y + 1, line[..indent].chars().count(), line.trim())
+            });
+        read_grid(rows, &cell)
+    }
+}
+
+/// rows given as their line, the number of columns skipped before them and their text
+fn read_grid<'a, T, E, F>(
+    lines: impl Iterator<Item = (usize, usize, &'a str)>,
+    cell: &F,
+) -> Result<Vec<Vec<T>>, GridError<E>>
+where
+    F: Fn(char) -> Result<T, E>,
+{
+    let mut rows = Vec::<Vec<T>>::new();
+
+    for (line, indent, text) in lines {
+        let row = text
+            .chars()
+            .enumerate()
+            .map(|(x, symbol)| {
+                cell(symbol).map_err(|error| GridError::Cell {
+                    line,
+                    column: indent + x + 1,
+                    symbol,
+                    error,
+                })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        match rows.first() {
+            Some(first) if first.len() != row.len() => {
+                return Err(GridError::Ragged {
+                    line,
+                    width: row.len(),
+                    expected: first.len(),
+                });
+            }
+            _ => rows.push(row),
+        }
+    }
+
+    if rows.iter().all(Vec::is_empty) {
+        Err(GridError::Empty)
+    } else {
+        Ok(rows)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn digit(c: char) -> Result<u32, &'static str> {
+        c.to_digit(10).ok_or("is not a digit")
+    }
+
+    #[test]
+    fn typed_grid() {
+        assert_eq!(
+            Ok(vec![vec![1, 2, 3], vec![4, 5, 6]]),
+            grid_of(digit)("123\n456\n")
+        );
+    }
+
+    #[test]
+    fn grid_errors() {
+        assert_eq!(Err(GridError::Empty), grid_of(digit)(""));
+        assert_eq!(
+            Err(GridError::Ragged {
+                line: 3,
+                width: 2,
+                expected: 3
+            }),
+            grid_of(digit)("123\n456\n78")
+        );
+        let error = grid_of(digit)("123\n4x6").unwrap_err();
+        assert_eq!(
+            GridError::Cell {
+                line: 2,
+                column: 2,
+                symbol: 'x',
+                error: "is not a digit"
+            },
+            error
+        );
+        assert_eq!("line 2:2: 'x' is not a digit", error.to_string());
+    }
+
+    #[test]
+    fn trimmed_grid() {
+        assert_eq!(
+            Ok(vec![vec![1, 2], vec![3, 4]]),
+            trimmed_grid_of(digit)("\n  12 \n\t34\r\n\n")
+        );
+        assert_eq!(
+            Err(GridError::Cell {
+                line: 3,
+                column: 4,
+                symbol: 'x',
+                error: "is not a digit"
+            }),
+            trimmed_grid_of(digit)("\n 12\n  3x")
+        );
+        assert_eq!(
+            Err(GridError::Ragged {
+                line: 4,
+                width: 1,
+                expected: 2
+            }),
+            trimmed_grid_of(digit)("12\n34\n\n 5")
+        );
+        assert_eq!(Err(GridError::Empty), trimmed_grid_of(digit)(" \n\n"));
+    }
+}
@@ -0,0 +1,121 @@
+use std::fmt::Debug;
+
+/// anything parsers can walk over, e.g. `&str` yielding chars or `&[u8]` yielding bytes
+pub trait Input: Copy + Debug + PartialEq {
+    type Item: Copy + PartialEq;
+
+    const NEWLINE: Self::Item;
+
+    /// length in the units `split_at_position` expects, i.e. bytes for both `&str` and `&[u8]`
+    fn input_len(&self) -> usize;
+
+    fn next_item(&self) -> Option<(Self::Item, Self)>;
+
+    /// offset of the first item matching the predicate
+    fn position<P>(&self, predicate: P) -> Option<usize>
+    where
+        P: Fn(Self::Item) -> bool;
+
+    fn split_at_position(&self, mid: usize) -> (Self, Self);
+
+    /// offset of this input inside of `source`, `None` unless it's a subslice of it
+    fn offset_from(&self, source: &Self) -> Option<usize>;
+
+    /// rest of the input after `literal`, which is given as text whatever the input is made of
+    fn strip_literal(&self, literal: &str) -> Option<Self>;
+
+    /// input without its leading ASCII or Unicode whitespace
+    fn trim_start(&self) -> Self;
+}
+
+impl Input for &str {
+    type Item = char;
+
+    const NEWLINE: Self::Item = '\n';
+
+    fn input_len(&self) -> usize {
+        self.len()
+    }
+
+    fn next_item(&self) -> Option<(Self::Item, Self)> {
+        let c = self.chars().next()?;
+        Some((c, &self[c.len_utf8()..]))
+    }
+
+    fn position<P>(&self, predicate: P) -> Option<usize>
+    where
+        P: Fn(Self::Item) -> bool,
+    {
+        self.find(predicate)
+    }
+
+    fn split_at_position(&self, mid: usize) -> (Self, Self) {
+        self.split_at(mid)
+    }
+
+    fn offset_from(&self, source: &Self) -> Option<usize> {
+        subslice_offset(self.as_bytes(), source.as_bytes())
+    }
+
+    fn strip_literal(&self, literal: &str) -> Option<Self> {
+        self.strip_prefix(literal)
+    }
+
+    fn trim_start(&self) -> Self {
+        str::trim_start(self)
+    }
+}
+
+impl Input for &[u8] {
+    type Item = u8;
+
+    const NEWLINE: Self::Item = b'\n';
+
+    fn input_len(&self) -> usize {
+        self.len()
+    }
+
+    fn next_item(&self) -> Option<(Self::Item, Self)> {
+        let (&head, tail) = self.split_first()?;
+        Some((head, tail))
+    }
+
+    fn position<P>(&self, predicate: P) -> Option<usize>
+    where
+        P: Fn(Self::Item) -> bool,
+    {
+        self.iter().position(|&b| predicate(b))
+    }
+
+    fn split_at_position(&self, mid: usize) -> (Self, Self) {
+        self.split_at(mid)
+    }
+
+    fn offset_from(&self, source: &Self) -> Option<usize> {
+        subslice_offset(self, source)
+    }
+
+    fn strip_literal(&self, literal: &str) -> Option<Self> {
+        self.strip_prefix(literal.as_bytes())
+    }
+
+    fn trim_start(&self) -> Self {
+        let start = self
+            .iter()
+            .position(|b| !b.is_ascii_whitespace())
+            .unwrap_or(self.len());
+        &self[start..]
+    }
+}
+
+/// compares addresses rather than subtracting them right away, which would wrap around
+/// for a slice coming before `source`
+fn subslice_offset(slice: &[u8], source: &[u8]) -> Option<usize> {
+    let start = source.as_ptr() as usize;
+    let at = slice.as_ptr() as usize;
+    if at >= start && at + slice.len() <= start + source.len() {
+        Some(at - start)
+    } else {
+        None
+    }
+}
@@ -0,0 +1,189 @@
+use super::*;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// identity of a memoized parser, unique for the whole program so that
+/// two parsers sharing a table can never see each other's results
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MemoRule(usize);
+
+impl MemoRule {
+    #[allow(clippy::new_without_default)]
+    pub fn new() -> Self {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        Self(NEXT.fetch_add(1, Ordering::Relaxed))
+    }
+}
+
+/// rule and remaining input length
+type MemoKey = (MemoRule, usize);
+type MemoEntry<Output, I> = Result<(I, Output), ParseError<I>>;
+
+/// packrat cache of parse results keyed by rule and input position,
+/// a single table must only be used on inputs sliced from the same source
+/// since the position is derived from the remaining input length
+pub struct Memo<Output, I: Input> {
+    cache: RefCell<HashMap<MemoKey, MemoEntry<Output, I>>>,
+}
+
+impl<Output, I: Input> Memo<Output, I> {
+    pub fn new() -> Self {
+        Self {
+            cache: RefCell::new(HashMap::new()),
+        }
+    }
+
+    #[allow(dead_code)]
+    pub fn len(&self) -> usize {
+        self.cache.borrow().len()
+    }
+
+    #[allow(dead_code)]
+    pub fn is_empty(&self) -> bool {
+        self.cache.borrow().is_empty()
+    }
+}
+
+impl<Output, I: Input> Default for Memo<Output, I> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// runs `parser` at most once per input position,
+/// which turns heavily backtracking grammars from exponential into linear time
+#[allow(dead_code)]
+pub fn memoize<'a, 'm, I, P, Output>(
+    memo: &'m Memo<Output, I>,
+    parser: P,
+) -> impl Parser<'a, Output, I> + 'm
+where
+    I: Input + 'm,
+    P: Parser<'a, Output, I> + 'm,
+    Output: Clone,
+{
+    memoize_rule(memo, MemoRule::new(), parser)
+}
+
+/// `memoize` for a recursive rule that builds its parser anew on every call,
+/// `rule` is what keeps the results of all those parsers together
+#[allow(dead_code)]
+pub fn memoize_rule<'a, 'm, I, P, Output>(
+    memo: &'m Memo<Output, I>,
+    rule: MemoRule,
+    parser: P,
+) -> impl Parser<'a, Output, I> + 'm
+where
+    I: Input + 'm,
+    P: Parser<'a, Output, I> + 'm,
+    Output: Clone,
+{
+    move |input: I| {
+        let key = (rule, input.input_len());
+        if let Some(result) = memo.cache.borrow().get(&key) {
+            return result.clone();
+        }
+
+        // cache must not stay borrowed while parsing, rules are usually recursive
+        let result = parser.parse(input);
+        memo.cache.borrow_mut().insert(key, result.clone());
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::Cell;
+
+    /// `nested <- 'x' nested 'y' / 'x' nested 'z' / ''`
+    ///
+    /// on "xx..zz" the first alternative parses the whole inner part
+    /// before failing on 'z', so the second one has to parse it all over again
+    struct Nested<'a, 'm> {
+        calls: Cell<usize>,
+        memo: Option<&'m Memo<(), &'a str>>,
+        rule: MemoRule,
+    }
+
+    impl<'a, 'm> Nested<'a, 'm> {
+        fn new(memo: Option<&'m Memo<(), &'a str>>) -> Self {
+            Self {
+                calls: Cell::new(0),
+                memo,
+                rule: MemoRule::new(),
+            }
+        }
+
+        fn parse(&self, input: &'a str) -> ParseResult<'a, ()> {
+            let rule = move |input: &'a str| {
+                self.calls.set(self.calls.get() + 1);
+                let inner = move |input: &'a str| self.parse(input);
+                let wrapped = |closing| {
+                    map(
+                        pair(match_literal("x"), pair(inner, match_literal(closing))),
+                        |_| (),
+                    )
+                };
+                either(
+                    either(wrapped("y"), wrapped("z")),
+                    map(match_literal(""), |_| ()),
+                )
+                .parse(input)
+            };
+
+            match self.memo {
+                Some(memo) => memoize_rule(memo, self.rule, rule).parse(input),
+                None => rule(input),
+            }
+        }
+    }
+
+    fn pathological_input(depth: usize) -> String {
+        "x".repeat(depth) + &"z".repeat(depth)
+    }
+
+    #[test]
+    fn backtracking_is_exponential_without_memo() {
+        let input = pathological_input(12);
+        let grammar = Nested::new(None);
+        assert_eq!(Ok(("", ())), grammar.parse(&input));
+        assert!(grammar.calls.get() >= 1 << 12);
+    }
+
+    #[test]
+    fn backtracking_is_linear_with_memo() {
+        let input = pathological_input(12);
+        let memo = Memo::new();
+        let grammar = Nested::new(Some(&memo));
+        assert_eq!(Ok(("", ())), grammar.parse(&input));
+        assert_eq!(13, grammar.calls.get());
+        assert_eq!(13, memo.len());
+
+        let input = pathological_input(100);
+        let memo = Memo::new();
+        let grammar = Nested::new(Some(&memo));
+        assert_eq!(Ok(("", ())), grammar.parse(&input));
+        assert_eq!(101, grammar.calls.get());
+    }
+
+    #[test]
+    fn memoized_failures_are_cached_too() {
+        let memo = Memo::new();
+        let parser = memoize(&memo, pred(any_char(), char::is_ascii_digit));
+        assert_eq!(Err(ParseError::Mismatch("x")), parser.parse("x"));
+        assert_eq!(Err(ParseError::Mismatch("x")), parser.parse("x"));
+        assert_eq!(1, memo.len());
+    }
+
+    #[test]
+    fn parsers_sharing_a_memo_are_kept_apart() {
+        let memo = Memo::new();
+        let digit = memoize(&memo, pred(any_char(), char::is_ascii_digit));
+        let letter = memoize(&memo, pred(any_char(), char::is_ascii_alphabetic));
+        assert_eq!(Ok(("", '1')), digit.parse("1"));
+        assert_eq!(Err(ParseError::Mismatch("1")), letter.parse("1"));
+        assert_eq!(2, memo.len());
+    }
+}
@@ -1,19 +1,69 @@
 ///
 /// made with guidance of excellent https://bodil.lol/parser-combinators/
 ///
-use std::iter::once;
+use std::num::ParseIntError;
+use std::str::FromStr;
 
-pub type ParseResult<'a, Output> = Result<(&'a str, Output), &'a str>;
+pub mod bytes;
+mod chars;
+mod grammar;
+mod grid;
+mod input;
+mod memo;
+mod parse;
+mod precedence;
+mod recovery;
+mod span;
+mod streaming;
+mod trace;
+mod whole_input;
 
-pub trait Parser<'a, Output> {
-    fn parse(&self, input: &'a str) -> ParseResult<'a, Output>;
+pub use chars::*;
+pub use grammar::*;
+pub use grid::*;
+pub use input::Input;
+#[allow(unused_imports)]
+pub use memo::*;
+pub use parse::*;
+pub use precedence::*;
+#[allow(unused_imports)]
+pub use recovery::*;
+pub use span::*;
+#[allow(unused_imports)]
+pub use streaming::*;
+pub use trace::*;
+pub use whole_input::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseError<I> {
+    /// input doesn't match what the parser expects
+    Mismatch(I),
+    /// input is a well-formed number that doesn't fit into the requested type
+    Overflow(I),
+}
+
+impl<I: Input> ParseError<I> {
+    /// remaining input at the point of failure
+    #[allow(dead_code)]
+    pub fn input(&self) -> I {
+        match *self {
+            ParseError::Mismatch(input) | ParseError::Overflow(input) => input,
+        }
+    }
+}
+
+pub type ParseResult<'a, Output, I = &'a str> = Result<(I, Output), ParseError<I>>;
+
+pub trait Parser<'a, Output, I: Input = &'a str> {
+    fn parse(&self, input: I) -> ParseResult<'a, Output, I>;
 }
 
-impl<'a, F, Output> Parser<'a, Output> for F
+impl<'a, F, Output, I> Parser<'a, Output, I> for F
 where
-    F: Fn(&'a str) -> ParseResult<'a, Output>,
+    I: Input,
+    F: Fn(I) -> ParseResult<'a, Output, I>,
 {
-    fn parse(&self, input: &'a str) -> ParseResult<'a, Output> {
+    fn parse(&self, input: I) -> ParseResult<'a, Output, I> {
         self(input)
     }
 }
@@ -22,14 +72,15 @@ where
 pub fn match_literal<'a>(expected: &'static str) -> impl Parser<'a, ()> {
     move |input: &'a str| match input.get(0..expected.len()) {
         Some(slice) if slice == expected => Ok((&input[expected.len()..], ())),
-        _ => Err(input),
+        _ => Err(ParseError::Mismatch(input)),
     }
 }
 
 #[allow(dead_code)]
-pub fn one_or_more<'a, P, A>(parser: P) -> impl Parser<'a, Vec<A>>
+pub fn one_or_more<'a, I, P, A>(parser: P) -> impl Parser<'a, Vec<A>, I>
 where
-    P: Parser<'a, A>,
+    I: Input,
+    P: Parser<'a, A, I>,
 {
     move |mut input| {
         let mut result = Vec::new();
@@ -38,7 +89,7 @@ where
             input = next_input;
             result.push(first_item);
         } else {
-            return Err(input);
+            return Err(ParseError::Mismatch(input));
         }
 
         while let Ok((next_input, next_item)) = parser.parse(input) {
@@ -51,9 +102,10 @@ where
 }
 
 #[allow(dead_code)]
-pub fn zero_or_more<'a, P, A>(parser: P) -> impl Parser<'a, Vec<A>>
+pub fn zero_or_more<'a, I, P, A>(parser: P) -> impl Parser<'a, Vec<A>, I>
 where
-    P: Parser<'a, A>,
+    I: Input,
+    P: Parser<'a, A, I>,
 {
     move |mut input| {
         let mut result = Vec::new();
@@ -68,17 +120,18 @@ where
 }
 
 #[allow(dead_code)]
-pub fn one_or_more_until<'a, P1, P2, Output, Discard>(
+pub fn one_or_more_until<'a, I, P1, P2, Output, Discard>(
     parser_1: P1,
     parser_2: P2,
-) -> impl Parser<'a, Vec<Output>>
+) -> impl Parser<'a, Vec<Output>, I>
 where
-    P1: Parser<'a, Output>,
-    P2: Parser<'a, Discard>,
+    I: Input,
+    P1: Parser<'a, Output, I>,
+    P2: Parser<'a, Discard, I>,
 {
-    move |input: &'a str| {
+    move |input: I| {
         if let Ok(_) = parser_2.parse(input) {
-            return Err(input);
+            return Err(ParseError::Mismatch(input));
         }
 
         let (mut input_cursor, first_result) = parser_1.parse(input)?;
@@ -94,15 +147,16 @@ where
 }
 
 #[allow(dead_code)]
-pub fn zero_or_more_until<'a, P1, P2, Output, Discard>(
+pub fn zero_or_more_until<'a, I, P1, P2, Output, Discard>(
     parser_1: P1,
     parser_2: P2,
-) -> impl Parser<'a, Vec<Output>>
+) -> impl Parser<'a, Vec<Output>, I>
 where
-    P1: Parser<'a, Output>,
-    P2: Parser<'a, Discard>,
+    I: Input,
+    P1: Parser<'a, Output, I>,
+    P2: Parser<'a, Discard, I>,
 {
-    move |input: &'a str| {
+    move |input: I| {
         let mut input_cursor = input;
         let mut results = Vec::new();
         while let Err(_) = parser_2.parse(input_cursor) {
@@ -116,10 +170,11 @@ where
 }
 
 #[allow(dead_code)]
-pub fn pair<'a, P1, P2, R1, R2>(parser_1: P1, parser_2: P2) -> impl Parser<'a, (R1, R2)>
+pub fn pair<'a, I, P1, P2, R1, R2>(parser_1: P1, parser_2: P2) -> impl Parser<'a, (R1, R2), I>
 where
-    P1: Parser<'a, R1>,
-    P2: Parser<'a, R2>,
+    I: Input,
+    P1: Parser<'a, R1, I>,
+    P2: Parser<'a, R2, I>,
 {
     move |input| {
         let (tail, result_1) = parser_1.parse(input)?;
@@ -129,10 +184,11 @@ where
 }
 
 #[allow(dead_code)]
-pub fn either<'a, P1, P2, Output>(parser_1: P1, parser_2: P2) -> impl Parser<'a, Output>
+pub fn either<'a, I, P1, P2, Output>(parser_1: P1, parser_2: P2) -> impl Parser<'a, Output, I>
 where
-    P1: Parser<'a, Output>,
-    P2: Parser<'a, Output>,
+    I: Input,
+    P1: Parser<'a, Output, I>,
+    P2: Parser<'a, Output, I>,
 {
     move |input| match parser_1.parse(input) {
         result @ Ok(_) => result,
@@ -141,9 +197,10 @@ where
 }
 
 #[allow(dead_code)]
-pub fn map<'a, P, F, A, B>(parser: P, map_fn: F) -> impl Parser<'a, B>
+pub fn map<'a, I, P, F, A, B>(parser: P, map_fn: F) -> impl Parser<'a, B, I>
 where
-    P: Parser<'a, A>,
+    I: Input,
+    P: Parser<'a, A, I>,
     F: Fn(A) -> B,
 {
     move |input| {
@@ -154,9 +211,10 @@ where
 }
 
 #[allow(dead_code)]
-pub fn map_opt<'a, P, F, A, B>(parser: P, map_fn: F) -> impl Parser<'a, B>
+pub fn map_opt<'a, I, P, F, A, B>(parser: P, map_fn: F) -> impl Parser<'a, B, I>
 where
-    P: Parser<'a, A>,
+    I: Input,
+    P: Parser<'a, A, I>,
     F: Fn(A) -> Option<B>,
 {
     move |input| {
@@ -164,41 +222,97 @@ where
         if let Some(mapped) = map_fn(result) {
             Ok((tail, mapped))
         } else {
-            Err(input)
+            Err(ParseError::Mismatch(input))
         }
     }
 }
 
 #[allow(dead_code)]
-pub fn left<'a, P1, P2, R1, R2>(parser1: P1, parser2: P2) -> impl Parser<'a, R1>
+pub fn left<'a, I, P1, P2, R1, R2>(parser1: P1, parser2: P2) -> impl Parser<'a, R1, I>
 where
-    P1: Parser<'a, R1>,
-    P2: Parser<'a, R2>,
+    I: Input,
+    P1: Parser<'a, R1, I>,
+    P2: Parser<'a, R2, I>,
 {
     map(pair(parser1, parser2), |(left, _right)| left)
 }
 
 #[allow(dead_code)]
-pub fn right<'a, P1, P2, R1, R2>(parser1: P1, parser2: P2) -> impl Parser<'a, R2>
+pub fn right<'a, I, P1, P2, R1, R2>(parser1: P1, parser2: P2) -> impl Parser<'a, R2, I>
 where
-    P1: Parser<'a, R1>,
-    P2: Parser<'a, R2>,
+    I: Input,
+    P1: Parser<'a, R1, I>,
+    P2: Parser<'a, R2, I>,
 {
     map(pair(parser1, parser2), |(_left, right)| right)
 }
 
+/// zero or more items separated by `separator`, a trailing separator is left unconsumed
 #[allow(dead_code)]
-pub fn any_char<'a>() -> impl Parser<'a, char> {
-    move |input: &'a str| match input.chars().next() {
-        Some(c) => Ok((&input[c.len_utf8()..], c)),
-        _ => Err(input),
+pub fn sep_by<'a, I, P1, P2, Output, Discard>(
+    parser: P1,
+    separator: P2,
+) -> impl Parser<'a, Vec<Output>, I>
+where
+    I: Input,
+    P1: Parser<'a, Output, I>,
+    P2: Parser<'a, Discard, I>,
+{
+    move |input: I| {
+        let mut results = Vec::new();
+        let mut input_cursor = match parser.parse(input) {
+            Ok((next_input, first_result)) => {
+                results.push(first_result);
+                next_input
+            }
+            Err(_) => return Ok((input, results)),
+        };
+
+        while let Ok((next_input, _)) = separator.parse(input_cursor) {
+            match parser.parse(next_input) {
+                Ok((next_input, result)) => {
+                    input_cursor = next_input;
+                    results.push(result);
+                }
+                Err(_) => break,
+            }
+        }
+
+        Ok((input_cursor, results))
+    }
+}
+
+#[allow(dead_code)]
+pub fn sep_by1<'a, I, P1, P2, Output, Discard>(
+    parser: P1,
+    separator: P2,
+) -> impl Parser<'a, Vec<Output>, I>
+where
+    I: Input,
+    P1: Parser<'a, Output, I>,
+    P2: Parser<'a, Discard, I>,
+{
+    pred(sep_by(parser, separator), |results| !results.is_empty())
+}
+
+#[allow(dead_code)]
+pub fn any_item<'a, I: Input>() -> impl Parser<'a, I::Item, I> {
+    move |input: I| match input.next_item() {
+        Some((item, tail)) => Ok((tail, item)),
+        None => Err(ParseError::Mismatch(input)),
     }
 }
 
 #[allow(dead_code)]
-pub fn pred<'a, P, R, F>(parser: P, predicate: F) -> impl Parser<'a, R>
+pub fn any_char<'a>() -> impl Parser<'a, char> {
+    any_item()
+}
+
+#[allow(dead_code)]
+pub fn pred<'a, I, P, R, F>(parser: P, predicate: F) -> impl Parser<'a, R, I>
 where
-    P: Parser<'a, R>,
+    I: Input,
+    P: Parser<'a, R, I>,
     F: Fn(&R) -> bool,
 {
     move |input| {
@@ -206,7 +320,7 @@ where
         if predicate(&result) {
             Ok((input_tail, result))
         } else {
-            Err(input)
+            Err(ParseError::Mismatch(input))
         }
     }
 }
@@ -227,25 +341,72 @@ pub fn space_0<'a>() -> impl Parser<'a, Vec<char>> {
 }
 
 #[allow(dead_code)]
-pub fn quoted_string<'a>() -> impl Parser<'a, String> {
-    map(
-        right(
-            match_literal("\""),
-            left(
-                zero_or_more(pred(any_char(), |c| *c != '"')),
-                match_literal("\""),
-            ),
-        ),
-        |chars| chars.into_iter().collect(),
+pub fn recognize<'a, I, P, R>(parser: P) -> impl Parser<'a, I, I>
+where
+    I: Input,
+    P: Parser<'a, R, I>,
+{
+    move |input: I| {
+        let (tail, _) = parser.parse(input)?;
+        let (recognized, _) = input.split_at_position(input.input_len() - tail.input_len());
+        Ok((tail, recognized))
+    }
+}
+
+#[allow(dead_code)]
+pub fn take_while<'a, I, F>(predicate: F) -> impl Parser<'a, I, I>
+where
+    I: Input,
+    F: Fn(I::Item) -> bool,
+{
+    move |input: I| {
+        let len = input
+            .position(|item| !predicate(item))
+            .unwrap_or_else(|| input.input_len());
+        let (taken, tail) = input.split_at_position(len);
+        Ok((tail, taken))
+    }
+}
+
+#[allow(dead_code)]
+pub fn take_while1<'a, I, F>(predicate: F) -> impl Parser<'a, I, I>
+where
+    I: Input,
+    F: Fn(I::Item) -> bool,
+{
+    pred(take_while(predicate), |slice: &I| slice.input_len() > 0)
+}
+
+/// consumes everything up to, but not including, the first occurrence of `pattern`
+#[allow(dead_code)]
+pub fn take_until<'a>(pattern: &'static str) -> impl Parser<'a, &'a str> {
+    move |input: &'a str| match input.find(pattern) {
+        Some(len) => Ok((&input[len..], &input[..len])),
+        None => Err(ParseError::Mismatch(input)),
+    }
+}
+
+#[allow(dead_code)]
+pub fn quoted_str<'a>() -> impl Parser<'a, &'a str> {
+    right(
+        match_literal("\""),
+        left(take_while(|c| c != '"'), match_literal("\"")),
     )
 }
 
+#[allow(dead_code)]
+pub fn quoted_string<'a>() -> impl Parser<'a, String> {
+    map(quoted_str(), String::from)
+}
+
+#[allow(dead_code)]
+pub fn word_str<'a>() -> impl Parser<'a, &'a str> {
+    take_while1(char::is_alphabetic)
+}
+
 #[allow(dead_code)]
 pub fn word<'a>() -> impl Parser<'a, String> {
-    map(
-        one_or_more(pred(any_char(), |c| c.is_alphabetic())),
-        |chars| chars.into_iter().collect(),
-    )
+    map(word_str(), String::from)
 }
 
 #[allow(dead_code)]
@@ -256,27 +417,130 @@ pub fn join<'a, P: Parser<'a, Vec<String>>>(
     map(parser, move |vector| vector.join(separator))
 }
 
+#[allow(dead_code)]
+pub fn identifier_str<'a>() -> impl Parser<'a, &'a str> {
+    recognize(pair(
+        pred(any_char(), |c| c.is_alphabetic()),
+        take_while(|c: char| c.is_alphanumeric() || ['-', '_'].contains(&c)),
+    ))
+}
+
 #[allow(dead_code)]
 pub fn identifier<'a>() -> impl Parser<'a, String> {
+    map(identifier_str(), String::from)
+}
+
+pub trait Integer: FromStr<Err = ParseIntError> + Copy {
+    const SIGNED: bool;
+
+    fn from_str_radix(src: &str, radix: u32) -> Result<Self, ParseIntError>;
+}
+
+macro_rules! impl_integer {
+    ($signed:expr => $($t:ty)*) => {
+        $(
+            impl Integer for $t {
+                const SIGNED: bool = $signed;
+
+                fn from_str_radix(src: &str, radix: u32) -> Result<Self, ParseIntError> {
+                    <$t>::from_str_radix(src, radix)
+                }
+            }
+        )*
+    };
+}
+
+impl_integer!(false => u8 u16 u32 u64 u128 usize);
+impl_integer!(true => i8 i16 i32 i64 i128 isize);
+
+/// panics right away for a radix outside of `2..=36` instead of on the first input
+fn digits<'a>(radix: u32) -> impl Parser<'a, &'a str> {
+    assert!(
+        (2..=36).contains(&radix),
+        "radix must be between 2 and 36, got {}",
+        radix
+    );
+    // char::is_digit only accepts ASCII, unlike char::is_numeric
+    take_while1(move |c: char| c.is_digit(radix))
+}
+
+fn sign<'a>() -> impl Parser<'a, Option<char>> {
     move |input: &'a str| match input.chars().next() {
-        Some(head) if head.is_alphabetic() => {
-            let tail = input
-                .chars()
-                .skip(1)
-                .take_while(|&c| c.is_alphanumeric() || ['-', '_'].contains(&c));
-            let ident: String = once(head).chain(tail).collect();
-            Ok((&input[ident.len()..], ident))
+        Some(c @ '+') | Some(c @ '-') => Ok((&input[1..], Some(c))),
+        _ => Ok((input, None)),
+    }
+}
+
+/// input is expected to only contain an optional sign and valid digits at this point,
+/// so the only way for conversion to fail is the value being out of range
+fn integer_from_str<'a, T: Integer>(
+    input: &'a str,
+    tail: &'a str,
+    src: &str,
+    radix: u32,
+) -> ParseResult<'a, T> {
+    match T::from_str_radix(src, radix) {
+        Ok(value) => Ok((tail, value)),
+        Err(_) => Err(ParseError::Overflow(input)),
+    }
+}
+
+#[allow(dead_code)]
+pub fn unsigned_radix<'a, T: Integer>(radix: u32) -> impl Parser<'a, T> {
+    let digits = digits(radix);
+    move |input: &'a str| {
+        let (tail, digits) = digits.parse(input)?;
+        integer_from_str(input, tail, digits, radix)
+    }
+}
+
+#[allow(dead_code)]
+pub fn signed_radix<'a, T: Integer>(radix: u32) -> impl Parser<'a, T> {
+    let sign = sign();
+    let digits = digits(radix);
+    move |input: &'a str| {
+        let (after_sign, sign) = sign.parse(input)?;
+        let (tail, digits) = digits
+            .parse(after_sign)
+            .map_err(|_| ParseError::Mismatch(input))?;
+        match sign {
+            Some('-') if !T::SIGNED => Err(ParseError::Mismatch(input)),
+            Some('-') => {
+                let src = &input[..input.len() - tail.len()];
+                integer_from_str(input, tail, src, radix)
+            }
+            _ => integer_from_str(input, tail, digits, radix),
         }
-        _ => Err(input),
     }
 }
 
+/// decimal digits without a sign, e.g. `42`
+#[allow(dead_code)]
+pub fn unsigned<'a, T: Integer>() -> impl Parser<'a, T> {
+    unsigned_radix(10)
+}
+
+/// decimal digits with an optional `+` or `-` sign, e.g. `+3` or `-7`
+#[allow(dead_code)]
+pub fn signed<'a, T: Integer>() -> impl Parser<'a, T> {
+    signed_radix(10)
+}
+
+/// hexadecimal digits in either case without a prefix, e.g. `ff00A0`
+#[allow(dead_code)]
+pub fn hex<'a, T: Integer>() -> impl Parser<'a, T> {
+    unsigned_radix(16)
+}
+
+/// binary digits without a prefix, e.g. `101`
+#[allow(dead_code)]
+pub fn binary<'a, T: Integer>() -> impl Parser<'a, T> {
+    unsigned_radix(2)
+}
+
 #[allow(dead_code)]
 pub fn number<'a>() -> impl Parser<'a, u32> {
-    map_opt(
-        one_or_more(pred(any_char(), |c| c.is_numeric())),
-        |digits| digits.into_iter().collect::<String>().parse().ok(),
-    )
+    unsigned()
 }
 
 #[cfg(test)]
@@ -291,7 +555,10 @@ mod tests {
             Ok((" Hello, Robert!", ())),
             parse_joe.parse("Hello, Joe! Hello, Robert!")
         );
-        assert_eq!(Err("Hello, Mike!"), parse_joe.parse("Hello, Mike!"));
+        assert_eq!(
+            Err(ParseError::Mismatch("Hello, Mike!")),
+            parse_joe.parse("Hello, Mike!")
+        );
     }
 
     #[test]
@@ -305,7 +572,7 @@ mod tests {
             identifier().parse("two identifiers")
         );
         assert_eq!(
-            Err("#no-identifiers"),
+            Err(ParseError::Mismatch("#no-identifiers")),
             identifier().parse("#no-identifiers")
         );
     }
@@ -317,8 +584,11 @@ mod tests {
             Ok(("/>", ((), "my-first-element".into()))),
             tag_opener.parse("<my-first-element/>")
         );
-        assert_eq!(Err("oops"), tag_opener.parse("oops"));
-        assert_eq!(Err("!oops"), tag_opener.parse("<!oops"));
+        assert_eq!(Err(ParseError::Mismatch("oops")), tag_opener.parse("oops"));
+        assert_eq!(
+            Err(ParseError::Mismatch("!oops")),
+            tag_opener.parse("<!oops")
+        );
     }
 
     #[test]
@@ -326,15 +596,18 @@ mod tests {
         let parser = either(match_literal("one"), match_literal("two"));
         assert_eq!(Ok((" two", ())), parser.parse("one two"));
         assert_eq!(Ok((" one", ())), parser.parse("two one"));
-        assert_eq!(Err("none"), parser.parse("none"));
+        assert_eq!(Err(ParseError::Mismatch("none")), parser.parse("none"));
     }
 
     #[test]
     fn left_combinator() {
         let tag_opener = left(match_literal("<"), identifier());
         assert_eq!(Ok(("/>", ())), tag_opener.parse("<my-first-element/>"));
-        assert_eq!(Err("oops"), tag_opener.parse("oops"));
-        assert_eq!(Err("!oops"), tag_opener.parse("<!oops"));
+        assert_eq!(Err(ParseError::Mismatch("oops")), tag_opener.parse("oops"));
+        assert_eq!(
+            Err(ParseError::Mismatch("!oops")),
+            tag_opener.parse("<!oops")
+        );
     }
 
     #[test]
@@ -344,16 +617,19 @@ mod tests {
             Ok(("/>", "my-first-element".into())),
             tag_opener.parse("<my-first-element/>")
         );
-        assert_eq!(Err("oops"), tag_opener.parse("oops"));
-        assert_eq!(Err("!oops"), tag_opener.parse("<!oops"));
+        assert_eq!(Err(ParseError::Mismatch("oops")), tag_opener.parse("oops"));
+        assert_eq!(
+            Err(ParseError::Mismatch("!oops")),
+            tag_opener.parse("<!oops")
+        );
     }
 
     #[test]
     fn one_or_more_combinator() {
         let parser = one_or_more(match_literal("ha"));
         assert_eq!(Ok(("", vec![(), (), ()])), parser.parse("hahaha"));
-        assert_eq!(Err("ahah"), parser.parse("ahah"));
-        assert_eq!(Err(""), parser.parse(""));
+        assert_eq!(Err(ParseError::Mismatch("ahah")), parser.parse("ahah"));
+        assert_eq!(Err(ParseError::Mismatch("")), parser.parse(""));
     }
 
     #[test]
@@ -371,8 +647,14 @@ mod tests {
             Ok((";", vec!["one".into(), "two".into(), "three".into()])),
             parser.parse("one two three;")
         );
-        assert_eq!(Err(";nothing"), parser.parse(";nothing"));
-        assert_eq!(Err("!error two;"), parser.parse("one !error two;"));
+        assert_eq!(
+            Err(ParseError::Mismatch(";nothing")),
+            parser.parse(";nothing")
+        );
+        assert_eq!(
+            Err(ParseError::Mismatch("!error two;")),
+            parser.parse("one !error two;")
+        );
     }
 
     #[test]
@@ -383,14 +665,17 @@ mod tests {
             parser.parse("one two three;")
         );
         assert_eq!(Ok((";nothing", Vec::new())), parser.parse(";nothing"));
-        assert_eq!(Err("!error two;"), parser.parse("one !error two;"));
+        assert_eq!(
+            Err(ParseError::Mismatch("!error two;")),
+            parser.parse("one !error two;")
+        );
     }
 
     #[test]
     fn predicate_combinator() {
         let parser = pred(any_char(), |c| *c == 'o');
         assert_eq!(Ok(("mg", 'o')), parser.parse("omg"));
-        assert_eq!(Err("lol"), parser.parse("lol"));
+        assert_eq!(Err(ParseError::Mismatch("lol")), parser.parse("lol"));
     }
 
     #[test]
@@ -409,7 +694,10 @@ mod tests {
         );
         assert_eq!(Ok((" word", "first".into())), word().parse("first word"));
         assert_eq!(Ok(("", "onlyWord".into())), word().parse("onlyWord"));
-        assert_eq!(Err("~no-words"), word().parse("~no-words"));
+        assert_eq!(
+            Err(ParseError::Mismatch("~no-words")),
+            word().parse("~no-words")
+        );
     }
 
     #[test]
@@ -427,6 +715,393 @@ mod tests {
             Ok((" and then some", 16746)),
             number().parse("16746 and then some")
         );
-        assert_eq!(Err("NaN"), number().parse("NaN"));
+        assert_eq!(Err(ParseError::Mismatch("NaN")), number().parse("NaN"));
+    }
+
+    #[test]
+    fn unsigned_parser() {
+        assert_eq!(Ok(("", 255u8)), unsigned().parse("255"));
+        assert_eq!(
+            Ok((" ", u64::MAX)),
+            unsigned().parse("18446744073709551615 ")
+        );
+        assert_eq!(
+            Err(ParseError::Overflow("256")),
+            unsigned::<u8>().parse("256")
+        );
+        assert_eq!(
+            Err(ParseError::Mismatch("+1")),
+            unsigned::<u8>().parse("+1")
+        );
+        assert_eq!(Err(ParseError::Mismatch("٣")), number().parse("٣"));
+    }
+
+    #[test]
+    fn signed_parser() {
+        assert_eq!(Ok(("", 3i32)), signed().parse("+3"));
+        assert_eq!(Ok(("", -7i32)), signed().parse("-7"));
+        assert_eq!(Ok((" +1", 42i64)), signed().parse("42 +1"));
+        assert_eq!(Ok(("", i8::MIN)), signed().parse("-128"));
+        assert_eq!(Ok(("", 1u8)), signed().parse("+1"));
+        assert_eq!(
+            Err(ParseError::Overflow("+128")),
+            signed::<i8>().parse("+128")
+        );
+        assert_eq!(
+            Err(ParseError::Overflow("-129")),
+            signed::<i8>().parse("-129")
+        );
+        assert_eq!(Err(ParseError::Mismatch("-1")), signed::<u8>().parse("-1"));
+        assert_eq!(Err(ParseError::Mismatch("-")), signed::<i8>().parse("-"));
+        assert_eq!(
+            Err(ParseError::Mismatch("--1")),
+            signed::<i8>().parse("--1")
+        );
+    }
+
+    #[test]
+    fn radix_parsers() {
+        assert_eq!(Ok(("g", 0xffu32)), hex().parse("ffg"));
+        assert_eq!(Ok(("", 0xABCDu16)), hex().parse("AbCd"));
+        assert_eq!(Err(ParseError::Overflow("100")), hex::<u8>().parse("100"));
+        assert_eq!(Ok(("2", 0b101u8)), binary().parse("1012"));
+        assert_eq!(Err(ParseError::Mismatch("2")), binary::<u8>().parse("2"));
+        assert_eq!(
+            Err(ParseError::Overflow("111111111")),
+            binary::<u8>().parse("111111111")
+        );
+        assert_eq!(Ok(("", -0x10i32)), signed_radix(16).parse("-10"));
+        assert_eq!(Ok(("", 35u8)), unsigned_radix(36).parse("z"));
+    }
+
+    #[test]
+    #[should_panic(expected = "radix must be between 2 and 36, got 37")]
+    fn radix_out_of_range() {
+        let _ = unsigned_radix::<u32>(37);
+    }
+
+    #[test]
+    #[should_panic(expected = "radix must be between 2 and 36, got 1")]
+    fn signed_radix_out_of_range() {
+        let _ = signed_radix::<i32>(1);
+    }
+
+    #[test]
+    fn recognize_combinator() {
+        let parser = recognize(one_or_more(match_literal("ha")));
+        assert_eq!(Ok(("!", "hahaha")), parser.parse("hahaha!"));
+        assert_eq!(Err(ParseError::Mismatch("ah")), parser.parse("ah"));
+    }
+
+    #[test]
+    fn take_while_combinators() {
+        assert_eq!(
+            Ok(("-rest", "abc")),
+            take_while(char::is_alphabetic).parse("abc-rest")
+        );
+        assert_eq!(
+            Ok(("-rest", "")),
+            take_while(char::is_alphabetic).parse("-rest")
+        );
+        assert_eq!(
+            Err(ParseError::Mismatch("-rest")),
+            take_while1(char::is_alphabetic).parse("-rest")
+        );
+        assert_eq!(
+            Ok(("", "ünï")),
+            take_while1(char::is_alphabetic).parse("ünï")
+        );
+    }
+
+    #[test]
+    fn take_until_combinator() {
+        let parser = take_until(" bags");
+        assert_eq!(
+            Ok((" bags contain", "pale turquoise")),
+            parser.parse("pale turquoise bags contain")
+        );
+        assert_eq!(Ok((" bags", "")), parser.parse(" bags"));
+        assert_eq!(Err(ParseError::Mismatch("no bag")), parser.parse("no bag"));
+    }
+
+    #[test]
+    fn slice_token_parsers() {
+        assert_eq!(Ok(("", "Hello Joe!")), quoted_str().parse("\"Hello Joe!\""));
+        assert_eq!(
+            Err(ParseError::Mismatch("")),
+            quoted_str().parse("\"unterminated")
+        );
+        assert_eq!(Ok((" word", "first")), word_str().parse("first word"));
+        assert_eq!(
+            Ok(("", "one-identifier_underscore")),
+            identifier_str().parse("one-identifier_underscore")
+        );
+        assert_eq!(
+            Err(ParseError::Mismatch("_nope")),
+            identifier_str().parse("_nope")
+        );
+    }
+
+    #[test]
+    fn sep_by_combinator() {
+        let parser = sep_by(number(), match_literal(", "));
+        assert_eq!(Ok(("", vec![1, 2, 3])), parser.parse("1, 2, 3"));
+        assert_eq!(Ok((", x", vec![1, 2])), parser.parse("1, 2, x"));
+        assert_eq!(Ok(("x", vec![])), parser.parse("x"));
+        assert_eq!(
+            Err(ParseError::Mismatch("x")),
+            sep_by1(number(), match_literal(", ")).parse("x")
+        );
+    }
+}
+
+#[cfg(test)]
+mod benches {
+    use super::*;
+    use test::{black_box, Bencher};
+
+    const SENTENCE: &str = "the quick brown fox jumps over the lazy dog ";
+
+    fn input() -> String {
+        SENTENCE.repeat(1000)
+    }
+
+    /// token parsers as they were before the slice based ones, collecting one char at a time
+    mod char_collecting {
+        use super::*;
+        use std::iter::once;
+
+        pub fn quoted_string<'a>() -> impl Parser<'a, String> {
+            map(
+                right(
+                    match_literal("\""),
+                    left(
+                        zero_or_more(pred(any_char(), |c| *c != '"')),
+                        match_literal("\""),
+                    ),
+                ),
+                |chars| chars.into_iter().collect(),
+            )
+        }
+
+        pub fn word<'a>() -> impl Parser<'a, String> {
+            map(
+                one_or_more(pred(any_char(), |c| c.is_alphabetic())),
+                |chars| chars.into_iter().collect(),
+            )
+        }
+
+        pub fn identifier<'a>() -> impl Parser<'a, String> {
+            move |input: &'a str| match input.chars().next() {
+                Some(head) if head.is_alphabetic() => {
+                    let tail = input
+                        .chars()
+                        .skip(1)
+                        .take_while(|&c| c.is_alphanumeric() || ['-', '_'].contains(&c));
+                    let ident: String = once(head).chain(tail).collect();
+                    Ok((&input[ident.len()..], ident))
+                }
+                _ => Err(ParseError::Mismatch(input)),
+            }
+        }
+    }
+
+    #[test]
+    fn char_collecting_parsers_agree() {
+        let input = input();
+        let quoted = format!("\"{}\"", input);
+        assert_eq!(
+            zero_or_more(left(word(), space_0())).parse(&input),
+            zero_or_more(left(char_collecting::word(), space_0())).parse(&input)
+        );
+        assert_eq!(
+            zero_or_more(left(identifier(), space_0())).parse(&input),
+            zero_or_more(left(char_collecting::identifier(), space_0())).parse(&input)
+        );
+        assert_eq!(
+            quoted_string().parse(&quoted),
+            char_collecting::quoted_string().parse(&quoted)
+        );
+    }
+
+    #[bench]
+    fn word_char_collecting(b: &mut Bencher) {
+        let input = input();
+        let parser = zero_or_more(left(char_collecting::word(), space_0()));
+        b.iter(|| black_box(parser.parse(&input)));
+    }
+
+    #[bench]
+    fn word_allocating(b: &mut Bencher) {
+        let input = input();
+        let parser = zero_or_more(left(word(), space_0()));
+        b.iter(|| black_box(parser.parse(&input)));
+    }
+
+    #[bench]
+    fn word_slice(b: &mut Bencher) {
+        let input = input();
+        let parser = zero_or_more(left(word_str(), space_0()));
+        b.iter(|| black_box(parser.parse(&input)));
+    }
+
+    #[bench]
+    fn identifier_char_collecting(b: &mut Bencher) {
+        let input = input();
+        let parser = zero_or_more(left(char_collecting::identifier(), space_0()));
+        b.iter(|| black_box(parser.parse(&input)));
+    }
+
+    #[bench]
+    fn identifier_allocating(b: &mut Bencher) {
+        let input = input();
+        let parser = zero_or_more(left(identifier(), space_0()));
+        b.iter(|| black_box(parser.parse(&input)));
+    }
+
+    #[bench]
+    fn identifier_slice(b: &mut Bencher) {
+        let input = input();
+        let parser = zero_or_more(left(identifier_str(), space_0()));
+        b.iter(|| black_box(parser.parse(&input)));
+    }
+
+    #[bench]
+    fn quoted_string_char_collecting(b: &mut Bencher) {
+        let input = format!("\"{}\"", input());
+        let parser = char_collecting::quoted_string();
+        b.iter(|| black_box(parser.parse(&input)));
+    }
+
+    #[bench]
+    fn quoted_string_allocating(b: &mut Bencher) {
+        let input = format!("\"{}\"", input());
+        b.iter(|| black_box(quoted_string().parse(&input)));
+    }
+
+    #[bench]
+    fn quoted_string_slice(b: &mut Bencher) {
+        let input = format!("\"{}\"", input());
+        b.iter(|| black_box(quoted_str().parse(&input)));
+    }
+}
+
+#[cfg(test)]
+mod properties {
+    use super::*;
+    use proptest::prelude::*;
+
+    proptest! {
+        #[test]
+        fn number_matches_str_parse(n in any::<u32>()) {
+            let text = n.to_string();
+            prop_assert_eq!(Ok(("", n)), number().parse(&text));
+        }
+
+        #[test]
+        fn digits_match_str_parse(text in "[0-9]{1,12}") {
+            match text.parse::<u32>() {
+                Ok(n) => prop_assert_eq!(Ok(("", n)), number().parse(&text)),
+                Err(_) => prop_assert_eq!(
+                    Err(ParseError::Overflow(text.as_str())),
+                    number().parse(&text)
+                ),
+            }
+        }
+
+        #[test]
+        fn signed_and_hex_round_trip(n in any::<i64>()) {
+            let text = n.to_string();
+            prop_assert_eq!(Ok(("", n)), signed().parse(&text));
+            let text = format!("{:x}", n as u64);
+            prop_assert_eq!(Ok(("", n as u64)), hex().parse(&text));
+        }
+
+        #[test]
+        fn sep_by_round_trips(words in prop::collection::vec("[a-z]{1,8}", 0..10)) {
+            let joined = words.join(", ");
+            let words = words.iter().map(String::as_str).collect::<Vec<_>>();
+            let parser = sep_by(word_str(), match_literal(", "));
+            prop_assert_eq!(Ok(("", words)), parser.parse(&joined));
+        }
+
+        #[test]
+        fn zero_or_more_never_fails(input in any::<String>()) {
+            let results = vec![
+                zero_or_more(word_str()).parse(&input),
+                zero_or_more(recognize(match_literal("ab"))).parse(&input),
+            ];
+            for result in results {
+                let (tail, matched) = result.unwrap();
+                prop_assert!(input.ends_with(tail));
+                let consumed = matched.iter().map(|m| m.len()).sum::<usize>();
+                prop_assert_eq!(input.len() - tail.len(), consumed);
+            }
+        }
+
+        #[test]
+        fn take_while_splits_input(input in any::<String>()) {
+            let (tail, taken) = take_while(char::is_alphanumeric).parse(input.as_str()).unwrap();
+            prop_assert_eq!(&input, &format!("{}{}", taken, tail));
+            prop_assert!(!tail.starts_with(char::is_alphanumeric));
+        }
+
+        #[test]
+        fn tag_no_case_ignores_case(word in "[bB][aA][gG][sS]?", tail in any::<String>()) {
+            let input = format!("{}{}", word, tail);
+            let (rest, matched) = tag_no_case("bag").parse(&input).unwrap();
+            prop_assert_eq!(&word[..3], matched);
+            prop_assert_eq!(&input[3..], rest);
+        }
+
+        #[test]
+        fn grids_round_trip(rows in (1usize..8).prop_flat_map(|width| {
+            prop::collection::vec(prop::collection::vec(any::<bool>(), width), 1..8)
+        })) {
+            let text = rows
+                .iter()
+                .map(|row| row.iter().map(|&cell| if cell { '#' } else { '.' }).collect::<String>())
+                .collect::<Vec<_>>()
+                .join("\n");
+            let parsed = grid_of(|c| match c {
+                '#' => Ok(true),
+                '.' => Ok(false),
+                _ => Err(()),
+            })(&text);
+            prop_assert_eq!(Ok(rows), parsed);
+        }
+
+        #[test]
+        fn lines_of_round_trips(numbers in prop::collection::vec(any::<u32>(), 0..20)) {
+            let text = numbers.iter().map(u32::to_string).collect::<Vec<_>>().join("\n");
+            prop_assert_eq!(Ok(numbers), lines_of(number())(&text));
+        }
+
+        /// failures point into the input rather than somewhere else
+        #[test]
+        fn errors_point_into_input(input in any::<String>()) {
+            let results = vec![
+                signed::<i32>().parse(&input).err(),
+                hex::<u16>().parse(&input).err(),
+                identifier_str().parse(&input).err(),
+                quoted_str().parse(&input).err(),
+                pair(one_of("+-"), class1('0'..='9')).parse(&input).map(|_| ()).err(),
+            ];
+            for error in results.into_iter().flatten() {
+                prop_assert!(input.ends_with(error.input()));
+            }
+        }
+
+        #[test]
+        fn structured_parsers_never_panic(input in any::<String>()) {
+            let _ = Grammar::from_rules(&input);
+            let _ = lines_of(signed::<i8>())(&input);
+            let _ = blocks_of(zero_or_more(any_char()))(&input);
+            let _ = recover_lines(number()).parse(&input);
+            let _ = precedence(number())
+                .infix("+", 1, Assoc::Left, |a, b| a.wrapping_add(b))
+                .group("(", ")")
+                .parse(&input);
+        }
     }
 }
@@ -0,0 +1,182 @@
+use super::*;
+
+pub use aoc_2020_derive::Parse;
+
+/// types that know how to parse themselves, usually implemented with `#[derive(Parse)]`
+/// and a format annotation like `#[parse("{min}-{max} {required_char}: {password}")]`:
+///
+/// * a field followed by a literal is parsed from the input up to that literal, in full
+/// * the last field, if not followed by a literal, takes whatever its own parser accepts
+/// * whitespace around fields is skipped, literals have to match exactly
+/// * enum variants are tried in declaration order, each with a format of its own
+///
+/// the generated code refers to `crate::parser_combinator`, so it only derives
+/// within this crate
+pub trait Parse: Sized {
+    fn parse_from(input: &str) -> ParseResult<'_, Self>;
+}
+
+/// `T::parse_from` as a parser usable with the other combinators
+#[allow(dead_code)]
+pub fn parsed<'a, T: Parse>() -> impl Parser<'a, T> {
+    T::parse_from
+}
+
+macro_rules! impl_parse_for_integers {
+    ($parser:ident: $($t:ty)*) => {
+        $(
+            impl Parse for $t {
+                fn parse_from(input: &str) -> ParseResult<'_, Self> {
+                    $parser().parse(input)
+                }
+            }
+        )*
+    };
+}
+
+impl_parse_for_integers!(unsigned: u8 u16 u32 u64 u128 usize);
+impl_parse_for_integers!(signed: i8 i16 i32 i64 i128 isize);
+
+impl Parse for char {
+    fn parse_from(input: &str) -> ParseResult<'_, Self> {
+        any_char().parse(input)
+    }
+}
+
+/// a run of non-whitespace characters
+impl Parse for String {
+    fn parse_from(input: &str) -> ParseResult<'_, Self> {
+        map(take_while1(|c: char| !c.is_whitespace()), String::from).parse(input)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq, Eq, Parse)]
+    #[parse("{min}-{max} {required_char}: {password}")]
+    struct Policy {
+        min: usize,
+        max: usize,
+        required_char: char,
+        password: String,
+    }
+
+    #[derive(Debug, PartialEq, Eq, Parse)]
+    enum Instruction {
+        #[parse("nop {0}")]
+        Nop(i32),
+        #[parse("acc {0}")]
+        Acc(i32),
+        #[parse("jmp {0}")]
+        Jmp(i32),
+        #[parse("halt")]
+        Halt,
+    }
+
+    #[derive(Debug, PartialEq, Eq, Parse)]
+    #[parse("{{{name}}} @ {line}: {instruction}")]
+    struct Listing {
+        name: String,
+        line: u32,
+        instruction: Instruction,
+    }
+
+    #[test]
+    fn derived_struct() {
+        assert_eq!(
+            Ok((
+                "",
+                Policy {
+                    min: 1,
+                    max: 3,
+                    required_char: 'a',
+                    password: "abcde".into()
+                }
+            )),
+            Policy::parse_from("1-3 a: abcde")
+        );
+        assert_eq!(
+            Ok((" rest", 'b')),
+            parsed::<Policy>()
+                .parse("1-3 b: cdefg rest")
+                .map(|(tail, policy)| (tail, policy.required_char))
+        );
+        assert_eq!(
+            Err(ParseError::Mismatch("x")),
+            Policy::parse_from("1-x a: abc")
+        );
+        assert_eq!(
+            Err(ParseError::Mismatch("b")),
+            Policy::parse_from("1-3 ab: abc")
+        );
+        assert_eq!(
+            Err(ParseError::Mismatch("1 3 a: abc")),
+            Policy::parse_from("1 3 a: abc")
+        );
+        assert_eq!(
+            Ok((
+                " ",
+                Policy {
+                    min: 1,
+                    max: 3,
+                    required_char: 'a',
+                    password: "abcde".into()
+                }
+            )),
+            Policy::parse_from("1-3 a : \tabcde ")
+        );
+    }
+
+    #[test]
+    fn unsigned_fields_take_no_sign() {
+        assert_eq!(Ok(("", 3)), u32::parse_from("3"));
+        assert_eq!(Err(ParseError::Mismatch("+3")), u32::parse_from("+3"));
+        assert_eq!(Err(ParseError::Mismatch("-3")), usize::parse_from("-3"));
+        assert_eq!(Ok(("", 3)), i32::parse_from("+3"));
+        assert_eq!(
+            Err(ParseError::Mismatch("+1")),
+            Policy::parse_from("+1-3 a: abc")
+        );
+    }
+
+    #[test]
+    fn derived_enum() {
+        assert_eq!(
+            Ok(("", Instruction::Nop(0))),
+            Instruction::parse_from("nop +0")
+        );
+        assert_eq!(
+            Ok(("", Instruction::Acc(-99))),
+            Instruction::parse_from("acc -99")
+        );
+        assert_eq!(
+            Ok((" 1", Instruction::Halt)),
+            Instruction::parse_from("halt 1")
+        );
+        assert_eq!(
+            Err(ParseError::Mismatch("jump +4")),
+            Instruction::parse_from("jump +4")
+        );
+        assert_eq!(
+            Ok(("", vec![Instruction::Jmp(4), Instruction::Acc(3)])),
+            sep_by(parsed(), match_literal("\n")).parse("jmp +4\nacc +3")
+        );
+    }
+
+    #[test]
+    fn derived_nested() {
+        assert_eq!(
+            Ok((
+                "",
+                Listing {
+                    name: "boot".into(),
+                    line: 7,
+                    instruction: Instruction::Jmp(-3)
+                }
+            )),
+            Listing::parse_from("{boot} @ 7: jmp -3")
+        );
+    }
+}
@@ -0,0 +1,266 @@
+///
+/// precedence climbing (Pratt) expression parser,
+/// operators bind tighter the higher their binding power is
+///
+use super::*;
+
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Assoc {
+    Left,
+    Right,
+}
+
+struct PrefixOp<T> {
+    symbol: &'static str,
+    binding_power: u8,
+    fold: Box<dyn Fn(T) -> T>,
+}
+
+struct InfixOp<T> {
+    symbol: &'static str,
+    binding_power: u8,
+    assoc: Assoc,
+    fold: Box<dyn Fn(T, T) -> T>,
+}
+
+impl<T> InfixOp<T> {
+    /// left and right binding powers, the side with the higher one wins an operand
+    /// sitting between two operators of the same precedence
+    fn binding_powers(&self) -> (u16, u16) {
+        let power = self.binding_power as u16 * 2;
+        match self.assoc {
+            Assoc::Left => (power, power + 1),
+            Assoc::Right => (power + 1, power),
+        }
+    }
+}
+
+/// nesting allowed by default, deep enough for any sensible expression
+/// while staying well clear of the stack size of a test thread
+const DEFAULT_MAX_DEPTH: usize = 256;
+
+pub struct Precedence<P, T> {
+    operand: P,
+    prefix: Vec<PrefixOp<T>>,
+    infix: Vec<InfixOp<T>>,
+    group: Option<(&'static str, &'static str)>,
+    max_depth: usize,
+}
+
+/// expression parser over `operand`, operators are declared with the builder methods
+/// and whitespace is allowed between all the tokens; symbols are given as text
+/// for byte inputs too
+#[allow(dead_code)]
+pub fn precedence<'a, I, P, T>(operand: P) -> Precedence<P, T>
+where
+    I: Input,
+    P: Parser<'a, T, I>,
+{
+    Precedence {
+        operand,
+        prefix: Vec::new(),
+        infix: Vec::new(),
+        group: None,
+        max_depth: DEFAULT_MAX_DEPTH,
+    }
+}
+
+#[allow(dead_code)]
+impl<P, T> Precedence<P, T> {
+    pub fn prefix<F>(mut self, symbol: &'static str, binding_power: u8, fold: F) -> Self
+    where
+        F: Fn(T) -> T + 'static,
+    {
+        self.prefix.push(PrefixOp {
+            symbol,
+            binding_power,
+            fold: Box::new(fold),
+        });
+        self
+    }
+
+    pub fn infix<F>(
+        mut self,
+        symbol: &'static str,
+        binding_power: u8,
+        assoc: Assoc,
+        fold: F,
+    ) -> Self
+    where
+        F: Fn(T, T) -> T + 'static,
+    {
+        self.infix.push(InfixOp {
+            symbol,
+            binding_power,
+            assoc,
+            fold: Box::new(fold),
+        });
+        self
+    }
+
+    /// brackets resetting the precedence for the expression inside of them
+    pub fn group(mut self, open: &'static str, close: &'static str) -> Self {
+        self.group = Some((open, close));
+        self
+    }
+
+    /// most subexpressions nested in each other, i.e. groups, prefix operators and
+    /// right associative operators in a row, deeper input is a mismatch where it gets too deep
+    pub fn max_depth(mut self, max_depth: usize) -> Self {
+        self.max_depth = max_depth;
+        self
+    }
+}
+
+/// longest symbol matching the start of the input, so `**` isn't mistaken for `*`
+fn match_symbol<I: Input, Op>(
+    input: I,
+    ops: &[Op],
+    symbol: impl Fn(&Op) -> &'static str,
+) -> Option<(I, &Op)> {
+    ops.iter()
+        .filter_map(|op| Some((input.strip_literal(symbol(op))?, op)))
+        .max_by_key(|(_, op)| symbol(op).len())
+}
+
+impl<P, T> Precedence<P, T> {
+    fn primary<'a, I>(&self, input: I, depth: usize) -> ParseResult<'a, T, I>
+    where
+        I: Input,
+        P: Parser<'a, T, I>,
+    {
+        let input = input.trim_start();
+
+        if let Some((tail, op)) = match_symbol(input, &self.prefix, |op| op.symbol) {
+            let (tail, operand) = self.expression(tail, op.binding_power as u16 * 2, depth)?;
+            return Ok((tail, (op.fold)(operand)));
+        }
+
+        if let Some((open, close)) = self.group {
+            if let Some(tail) = input.strip_literal(open) {
+                let (tail, inner) = self.expression(tail, 0, depth)?;
+                return match tail.trim_start().strip_literal(close) {
+                    Some(tail) => Ok((tail, inner)),
+                    None => Err(ParseError::Mismatch(tail.trim_start())),
+                };
+            }
+        }
+
+        self.operand.parse(input)
+    }
+
+    fn expression<'a, I>(
+        &self,
+        input: I,
+        min_binding_power: u16,
+        depth: usize,
+    ) -> ParseResult<'a, T, I>
+    where
+        I: Input,
+        P: Parser<'a, T, I>,
+    {
+        if depth >= self.max_depth {
+            return Err(ParseError::Mismatch(input));
+        }
+        let (mut input, mut lhs) = self.primary(input, depth + 1)?;
+
+        while let Some((tail, op)) = match_symbol(input.trim_start(), &self.infix, |op| op.symbol) {
+            let (left_power, right_power) = op.binding_powers();
+            if left_power < min_binding_power {
+                break;
+            }
+
+            let (tail, rhs) = self.expression(tail, right_power, depth + 1)?;
+            lhs = (op.fold)(lhs, rhs);
+            input = tail;
+        }
+
+        Ok((input, lhs))
+    }
+}
+
+impl<'a, I, P, T> Parser<'a, T, I> for Precedence<P, T>
+where
+    I: Input,
+    P: Parser<'a, T, I>,
+{
+    fn parse(&self, input: I) -> ParseResult<'a, T, I> {
+        self.expression(input, 0, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn arithmetic<'a>() -> Precedence<impl Parser<'a, i64>, i64> {
+        precedence(signed())
+            .prefix("-", 3, |x: i64| -x)
+            .infix("+", 1, Assoc::Left, |a, b| a + b)
+            .infix("-", 1, Assoc::Left, |a, b| a - b)
+            .infix("*", 2, Assoc::Left, |a, b| a * b)
+            .infix("**", 4, Assoc::Right, |a: i64, b| a.pow(b as u32))
+            .group("(", ")")
+    }
+
+    #[test]
+    fn folds_to_value() {
+        let parser = arithmetic();
+        assert_eq!(Ok(("", 7)), parser.parse("1 + 2 * 3"));
+        assert_eq!(Ok(("", 9)), parser.parse("(1 + 2) * 3"));
+        assert_eq!(Ok(("", 2)), parser.parse("10 - 5 - 3"));
+        assert_eq!(Ok(("", 512)), parser.parse("2 ** 3 ** 2"));
+        assert_eq!(Ok(("", -6)), parser.parse("- (1 + 2) * 2"));
+        assert_eq!(Ok((" ;", 3)), parser.parse("1 + 2 ;"));
+        assert_eq!(Err(ParseError::Mismatch("")), parser.parse("(1 + 2"));
+        assert_eq!(Err(ParseError::Mismatch("")), parser.parse("1 +"));
+    }
+
+    #[test]
+    fn builds_ast() {
+        let parser = precedence(map(word_str(), String::from))
+            .prefix("!", 5, |x| format!("(! {})", x))
+            .infix("&", 2, Assoc::Left, |a, b| format!("(& {} {})", a, b))
+            .infix("|", 1, Assoc::Left, |a, b| format!("(| {} {})", a, b))
+            .infix("->", 0, Assoc::Right, |a, b| format!("(-> {} {})", a, b))
+            .group("(", ")");
+        assert_eq!(
+            Ok(("", "(-> (| a (& (! b) c)) (-> d e))".into())),
+            parser.parse("a | !b & c -> d -> e")
+        );
+        assert_eq!(
+            Ok(("", "(& (| a b) c)".into())),
+            parser.parse("(a | b) & c")
+        );
+    }
+
+    #[test]
+    fn over_bytes() {
+        let parser = precedence(bytes::number())
+            .infix("+", 1, Assoc::Left, |a, b| a + b)
+            .infix("*", 2, Assoc::Left, |a, b| a * b)
+            .group("(", ")");
+        assert_eq!(Ok((&b""[..], 14)), parser.parse(&b"2 * (3 + 4)"[..]));
+        assert_eq!(
+            Err(ParseError::Mismatch(&b""[..])),
+            parser.parse(&b"(1 + 2"[..])
+        );
+    }
+
+    #[test]
+    fn nesting_is_limited() {
+        let nested = |depth| format!("{}1{}", "(".repeat(depth), ")".repeat(depth));
+        let within_limit = |input: &str| arithmetic().max_depth(64).parse(input).is_ok();
+        assert!(within_limit(&nested(60)));
+        assert!(!within_limit(&nested(70)));
+        assert!(!within_limit(&format!("{}1", "- ".repeat(100))));
+        assert!(!within_limit(&format!("{}2", "2 ** ".repeat(100))));
+
+        let deep = nested(100_000);
+        assert_eq!(
+            Err(ParseError::Mismatch(&deep[DEFAULT_MAX_DEPTH..])),
+            arithmetic().parse(&deep)
+        );
+    }
+}
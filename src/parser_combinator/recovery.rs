@@ -0,0 +1,164 @@
+use super::*;
+
+/// everything that could be parsed along with the problems skipped over on the way
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recovered<T, I> {
+    pub values: Vec<T>,
+    pub errors: Vec<ParseError<I>>,
+}
+
+/// input right after the next `separator` that consumes something,
+/// or the empty end of the input if there is none; a separator matching nothing
+/// (like `space_0`) would otherwise leave the input where it was
+fn skip_past<'a, I, P, D>(separator: &P, mut input: I) -> I
+where
+    I: Input,
+    P: Parser<'a, D, I>,
+{
+    loop {
+        if let Ok((tail, _)) = separator.parse(input) {
+            if tail.input_len() < input.input_len() {
+                return tail;
+            }
+        }
+
+        match input.next_item() {
+            Some((_, tail)) => input = tail,
+            None => return input,
+        }
+    }
+}
+
+/// parses the whole input as `parser` results delimited by `separator`,
+/// an item that fails to parse or has leftovers before the next separator is recorded
+/// as an error and skipped, so a single pass reports every malformed item;
+/// so is an item that, separator included, doesn't consume anything
+#[allow(dead_code)]
+pub fn recover_separated<'a, I, P, S, T, D>(
+    parser: P,
+    separator: S,
+) -> impl Parser<'a, Recovered<T, I>, I>
+where
+    I: Input,
+    P: Parser<'a, T, I>,
+    S: Parser<'a, D, I>,
+{
+    move |mut input: I| {
+        let mut values = Vec::new();
+        let mut errors = Vec::new();
+
+        while input.input_len() > 0 {
+            match parser.parse(input) {
+                Ok((tail, value)) => match separator.parse(tail) {
+                    Ok((tail, _)) if tail.input_len() < input.input_len() => {
+                        values.push(value);
+                        input = tail;
+                    }
+                    Err(_) if tail.input_len() == 0 => {
+                        values.push(value);
+                        input = tail;
+                    }
+                    Ok(_) => {
+                        errors.push(ParseError::Mismatch(input));
+                        input = skip_past(&separator, input);
+                    }
+                    Err(error) => {
+                        errors.push(error);
+                        input = skip_past(&separator, tail);
+                    }
+                },
+                Err(error) => {
+                    errors.push(error);
+                    input = skip_past(&separator, input);
+                }
+            }
+        }
+
+        Ok((input, Recovered { values, errors }))
+    }
+}
+
+/// one `parser` result per line ending in `\n` or `\r\n`, malformed lines are reported and skipped
+#[allow(dead_code)]
+pub fn recover_lines<'a, P, T>(parser: P) -> impl Parser<'a, Recovered<T, &'a str>>
+where
+    P: Parser<'a, T>,
+{
+    recover_separated(parser, line_ending())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recovers_at_separators() {
+        let parser = recover_separated(number(), match_literal(","));
+        assert_eq!(
+            Ok((
+                "",
+                Recovered {
+                    values: vec![1, 3, 5],
+                    errors: vec![
+                        ParseError::Mismatch("x,3,4y,5"),
+                        ParseError::Mismatch("y,5")
+                    ]
+                }
+            )),
+            parser.parse("1,x,3,4y,5")
+        );
+        assert_eq!(
+            Ok((
+                "",
+                Recovered {
+                    values: vec![],
+                    errors: vec![ParseError::Overflow("99999999999")]
+                }
+            )),
+            parser.parse("99999999999")
+        );
+    }
+
+    #[test]
+    fn recovers_whole_lines() {
+        let source = "1 + 2\n3 ? 4\n5 + 6\n\n7 +\n";
+        let sum = map(
+            pair(number(), right(match_literal(" + "), number())),
+            |(a, b)| a + b,
+        );
+        let (tail, recovered) = recover_lines(sum).parse(source).unwrap();
+        assert_eq!("", tail);
+        assert_eq!(vec![3, 11], recovered.values);
+
+        let locations = recovered
+            .errors
+            .iter()
+            .map(|error| Span::of(source, error.input()).unwrap().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["2:2", "4:1", "5:2"], locations);
+    }
+
+    #[test]
+    fn separators_matching_nothing_still_make_progress() {
+        let (_, recovered) = recover_separated(number(), space_0())
+            .parse("1 2 x 3")
+            .unwrap();
+        assert_eq!(vec![1, 2, 3], recovered.values);
+        assert_eq!(vec![ParseError::Mismatch("x 3")], recovered.errors);
+
+        let digits = take_while(|c: char| c.is_ascii_digit());
+        let (_, recovered) = recover_separated(digits, space_0())
+            .parse("12ab 3")
+            .unwrap();
+        assert_eq!(vec!["12", "3"], recovered.values);
+        assert_eq!(vec![ParseError::Mismatch("ab 3")], recovered.errors);
+    }
+
+    #[test]
+    fn recovers_crlf_lines() {
+        let (tail, recovered) = recover_lines(number()).parse("1\r\nx\r\n3\r\n").unwrap();
+        assert_eq!("", tail);
+        assert_eq!(vec![1, 3], recovered.values);
+        assert_eq!(vec![ParseError::Mismatch("x\r\n3\r\n")], recovered.errors);
+    }
+}
@@ -0,0 +1,209 @@
+use super::*;
+use std::cell::OnceCell;
+use std::fmt;
+
+/// position of a parsed item inside of the source it came from,
+/// offsets are in bytes while line and column are 1-based and counted in items
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Span {
+    pub start: usize,
+    pub end: usize,
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Span {
+    /// goes over `source` up to `start`, use a `LineIndex` for more than a few spans
+    pub fn new<I: Input>(source: I, start: usize, end: usize) -> Self {
+        LineIndex::new(source).span(source, start, end)
+    }
+
+    /// span of `fragment`, `None` unless it's a subslice of `source`
+    #[allow(dead_code)]
+    pub fn of<I: Input>(source: I, fragment: I) -> Option<Self> {
+        let start = fragment.offset_from(&source)?;
+        Some(Self::new(source, start, start + fragment.input_len()))
+    }
+}
+
+/// offsets where the lines of a source start, so finding the line and column of a span
+/// only goes over the line it's on instead of everything before it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LineIndex {
+    starts: Vec<usize>,
+}
+
+impl LineIndex {
+    pub fn new<I: Input>(source: I) -> Self {
+        let mut starts = vec![0];
+        let mut rest = source;
+        while let Some((item, tail)) = rest.next_item() {
+            if item == I::NEWLINE {
+                starts.push(source.input_len() - tail.input_len());
+            }
+            rest = tail;
+        }
+        Self { starts }
+    }
+
+    /// span from `start` to `end` of the `source` this index was built for
+    pub fn span<I: Input>(&self, source: I, start: usize, end: usize) -> Span {
+        let line = self
+            .starts
+            .partition_point(|&line_start| line_start <= start);
+        let (prefix, _) = source.split_at_position(start);
+        let (_, mut before) = prefix.split_at_position(self.starts[line - 1]);
+        let mut column = 1;
+        while let Some((_, tail)) = before.next_item() {
+            column += 1;
+            before = tail;
+        }
+
+        Span {
+            start,
+            end,
+            line,
+            column,
+        }
+    }
+}
+
+impl fmt::Display for Span {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Located<T> {
+    pub value: T,
+    pub span: Span,
+}
+
+impl<T> Located<T> {
+    pub fn map<U, F: FnOnce(T) -> U>(self, map_fn: F) -> Located<U> {
+        Located {
+            value: map_fn(self.value),
+            span: self.span,
+        }
+    }
+}
+
+impl<T: fmt::Display> fmt::Display for Located<T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} at {}", self.value, self.span)
+    }
+}
+
+/// wraps the parser output with its position in `source`,
+/// which is the whole text the parsed input was sliced from;
+/// the lines of `source` are indexed on first use and shared by every parse after it
+#[allow(dead_code)]
+pub fn spanned<'a, I, P, T>(source: I, parser: P) -> impl Parser<'a, Located<T>, I>
+where
+    I: Input,
+    P: Parser<'a, T, I>,
+{
+    let index = OnceCell::new();
+    move |input: I| {
+        let (tail, value) = parser.parse(input)?;
+        let offset = |slice: I| {
+            slice
+                .offset_from(&source)
+                .expect("spanned input must be a slice of its source")
+        };
+        let span =
+            index
+                .get_or_init(|| LineIndex::new(source))
+                .span(source, offset(input), offset(tail));
+        Ok((tail, Located { value, span }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn spanned_combinator() {
+        let source = "light red bags\nbright white bags";
+        let parser = spanned(source, word_str());
+
+        let (_, located) = parser.parse(source).unwrap();
+        assert_eq!("light", located.value);
+        assert_eq!(
+            Span {
+                start: 0,
+                end: 5,
+                line: 1,
+                column: 1
+            },
+            located.span
+        );
+
+        let (_, located) = parser.parse(&source[22..]).unwrap();
+        assert_eq!("white", located.value);
+        assert_eq!(
+            Span {
+                start: 22,
+                end: 27,
+                line: 2,
+                column: 8
+            },
+            located.span
+        );
+        assert_eq!("white at 2:8", located.to_string());
+    }
+
+    #[test]
+    fn spanned_columns_count_chars() {
+        let source = "ü ünï";
+        let (_, located) = spanned(source, word_str()).parse(&source[3..]).unwrap();
+        assert_eq!(3, located.span.start);
+        assert_eq!(3, located.span.column);
+    }
+
+    #[test]
+    fn spanned_over_bytes() {
+        let source: &[u8] = b"1\n22 333";
+        let parser = spanned(source, bytes::number());
+        let (_, located) = parser.parse(&source[5..]).unwrap();
+        assert_eq!(333, located.value);
+        assert_eq!(Span::new(source, 5, 8), located.span);
+        assert_eq!((2, 4), (located.span.line, located.span.column));
+    }
+
+    #[test]
+    fn span_of_fragment() {
+        let source = "a\nbc";
+        let span = Span::of(source, &source[3..]).unwrap();
+        assert_eq!((3, 4, 2, 2), (span.start, span.end, span.line, span.column));
+        assert_eq!(None, Span::of(&source[2..], &source[..1]));
+        assert_eq!(None, Span::of(&source[..2], &source[1..]));
+    }
+
+    #[test]
+    fn line_index() {
+        let source = "ab\n\nü x\n";
+        let index = LineIndex::new(source);
+        let at = |start| {
+            let span = index.span(source, start, start);
+            (span.line, span.column)
+        };
+        assert_eq!(
+            vec![(1, 1), (1, 3), (2, 1), (3, 1), (3, 2), (3, 3), (4, 1)],
+            [0, 2, 3, 4, 6, 7, 9]
+                .iter()
+                .map(|&start| at(start))
+                .collect::<Vec<_>>()
+        );
+        assert_eq!(Span::new(source, 7, 8), index.span(source, 7, 8));
+    }
+
+    #[test]
+    #[should_panic(expected = "spanned input must be a slice of its source")]
+    fn spanned_outside_of_source() {
+        let source = "light red";
+        let _ = spanned(source, word_str()).parse("dark red");
+    }
+}
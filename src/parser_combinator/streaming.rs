@@ -0,0 +1,407 @@
+///
+/// incremental parsing of inputs fed in chunks, e.g. from a `Read` source too big for memory
+///
+use super::*;
+use std::fmt;
+use std::io::{self, Read};
+
+/// outcome of running a parser on what has been received so far
+#[derive(Debug, PartialEq, Eq)]
+pub enum Partial<'a, T> {
+    /// value along with the input after it
+    Done(&'a str, T),
+    /// parser ran into the end of the received input, more of it could change the outcome
+    Incomplete,
+    Failed(ParseError<&'a str>),
+}
+
+/// runs `parser` on a prefix of the full input, `complete` tells whether there's any more of it;
+/// a parser that stopped exactly at the end of an incomplete input may want to keep going
+/// (a number cut in half by a chunk boundary), so that's reported as `Incomplete` too,
+/// failures before the end are left for the caller to judge
+#[allow(dead_code)]
+pub fn partial<'a, P, T>(parser: &P, input: &'a str, complete: bool) -> Partial<'a, T>
+where
+    P: Parser<'a, T>,
+{
+    match parser.parse(input) {
+        Ok((tail, _)) | Err(ParseError::Mismatch(tail)) | Err(ParseError::Overflow(tail))
+            if tail.is_empty() && !complete =>
+        {
+            Partial::Incomplete
+        }
+        Ok((tail, value)) => Partial::Done(tail, value),
+        Err(error) => Partial::Failed(error),
+    }
+}
+
+#[derive(Debug)]
+pub enum StreamError {
+    Io(io::Error),
+    /// byte `offset` from the start of the stream doesn't begin valid UTF-8
+    InvalidUtf8 {
+        offset: usize,
+    },
+    /// parse failure at byte `offset` from the start of the stream,
+    /// the error holds the rest of the line it happened on
+    Parse {
+        offset: usize,
+        error: ParseError<String>,
+    },
+}
+
+impl fmt::Display for StreamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StreamError::Io(error) => write!(f, "read failed: {}", error),
+            StreamError::InvalidUtf8 { offset } => write!(f, "byte {}: invalid UTF-8", offset),
+            StreamError::Parse {
+                offset,
+                error: ParseError::Mismatch(input),
+            } => write!(f, "byte {}: unexpected input {:?}", offset, input),
+            StreamError::Parse {
+                offset,
+                error: ParseError::Overflow(input),
+            } => write!(f, "byte {}: number out of range {:?}", offset, input),
+        }
+    }
+}
+
+const DEFAULT_CHUNK_SIZE: usize = 8 * 1024;
+const DEFAULT_LOOKAHEAD: usize = 1024;
+
+/// values parsed one after another from `reader`, only keeping the unparsed part of the
+/// input in memory; `parser` gets called with whatever text is buffered, so it has to be
+/// a closure like `|input: &str| left(number(), space_0()).parse(input)` that works for
+/// any lifetime and returns owned values
+///
+/// a failure is only final with `lookahead` bytes buffered past the point it happened at,
+/// otherwise it could be a literal cut by the chunk boundary; a parser succeeding without
+/// consuming anything would never get anywhere, so that's a failure too
+pub struct Records<R, F> {
+    reader: R,
+    parser: F,
+    /// text read so far, every byte is checked to be UTF-8 once as it comes in
+    buffer: String,
+    /// bytes read after `buffer`, a character split by the chunk boundary
+    pending: Vec<u8>,
+    /// bytes of `buffer` already parsed
+    start: usize,
+    /// bytes of the stream dropped from the front of `buffer`
+    dropped: usize,
+    chunk_size: usize,
+    lookahead: usize,
+    eof: bool,
+    /// stream offset of bytes that aren't UTF-8, reported once everything before them is parsed
+    invalid_utf8: Option<usize>,
+    failed: bool,
+}
+
+#[allow(dead_code)]
+pub fn records<R, F, T>(reader: R, parser: F) -> Records<R, F>
+where
+    R: Read,
+    F: Fn(&str) -> ParseResult<'_, T>,
+{
+    Records {
+        reader,
+        parser,
+        buffer: String::new(),
+        pending: Vec::new(),
+        start: 0,
+        dropped: 0,
+        chunk_size: DEFAULT_CHUNK_SIZE,
+        lookahead: DEFAULT_LOOKAHEAD,
+        eof: false,
+        invalid_utf8: None,
+        failed: false,
+    }
+}
+
+impl<R, F> Records<R, F> {
+    #[allow(dead_code)]
+    pub fn chunk_size(mut self, chunk_size: usize) -> Self {
+        assert!(chunk_size > 0, "chunk size must be positive");
+        self.chunk_size = chunk_size;
+        self
+    }
+
+    /// longest literal or token the parser could fail on before seeing all of it
+    #[allow(dead_code)]
+    pub fn lookahead(mut self, lookahead: usize) -> Self {
+        self.lookahead = lookahead;
+        self
+    }
+
+    /// bytes currently held in memory, parsed or not
+    #[allow(dead_code)]
+    pub fn buffered(&self) -> usize {
+        self.buffer.len() + self.pending.len()
+    }
+}
+
+impl<R: Read, F> Records<R, F> {
+    /// drops the parsed text and appends another chunk from the reader,
+    /// only its complete characters make it into `buffer`, nothing after invalid UTF-8 does
+    fn fill(&mut self) -> Result<(), StreamError> {
+        self.buffer.drain(..self.start);
+        self.dropped += self.start;
+        self.start = 0;
+
+        let len = self.pending.len();
+        self.pending.resize(len + self.chunk_size, 0);
+        let read = loop {
+            match self.reader.read(&mut self.pending[len..]) {
+                Err(error) if error.kind() == io::ErrorKind::Interrupted => continue,
+                result => break result,
+            }
+        };
+        self.pending.truncate(len + *read.as_ref().unwrap_or(&0));
+        self.eof = read.map_err(StreamError::Io)? == 0;
+
+        let (valid, invalid) = match std::str::from_utf8(&self.pending) {
+            Ok(text) => (text.len(), false),
+            Err(error) => (error.valid_up_to(), error.error_len().is_some()),
+        };
+        let text = std::str::from_utf8(&self.pending[..valid]).expect("checked above");
+        self.buffer.push_str(text);
+        self.pending.drain(..valid);
+        if invalid || (self.eof && !self.pending.is_empty()) {
+            self.invalid_utf8 = Some(self.dropped + self.buffer.len());
+        }
+        Ok(())
+    }
+}
+
+impl<R, F, T> Iterator for Records<R, F>
+where
+    R: Read,
+    F: Fn(&str) -> ParseResult<'_, T>,
+{
+    type Item = Result<T, StreamError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed {
+            return None;
+        }
+
+        loop {
+            let offset = self.dropped + self.start;
+            let text = &self.buffer[self.start..];
+            // nothing past invalid UTF-8 is ever going to be read
+            let complete = self.eof && self.pending.is_empty() || self.invalid_utf8.is_some();
+            let invalid_utf8 = self
+                .invalid_utf8
+                .map(|offset| StreamError::InvalidUtf8 { offset });
+            if text.is_empty() && complete {
+                self.failed = invalid_utf8.is_some();
+                return invalid_utf8.map(Err);
+            }
+
+            let rest = |input: &str| input.lines().next().unwrap_or("").to_owned();
+            let step = match partial(&self.parser, text, complete) {
+                // the parser wanted more than there is before the invalid bytes
+                Partial::Failed(error) if error.input().is_empty() && invalid_utf8.is_some() => {
+                    Err(invalid_utf8.unwrap())
+                }
+                Partial::Done(tail, _) if tail.len() == text.len() => Err(StreamError::Parse {
+                    offset,
+                    error: ParseError::Mismatch(rest(text)),
+                }),
+                Partial::Done(tail, value) => Ok(Some((text.len() - tail.len(), value))),
+                Partial::Incomplete => Ok(None),
+                Partial::Failed(error) if !complete && error.input().len() < self.lookahead => {
+                    Ok(None)
+                }
+                Partial::Failed(error) => Err(StreamError::Parse {
+                    offset: offset + text.len() - error.input().len(),
+                    error: match error {
+                        ParseError::Mismatch(input) => ParseError::Mismatch(rest(input)),
+                        ParseError::Overflow(input) => ParseError::Overflow(rest(input)),
+                    },
+                }),
+            };
+
+            let result = match step {
+                Ok(Some((consumed, value))) => {
+                    self.start += consumed;
+                    return Some(Ok(value));
+                }
+                Ok(None) => self.fill(),
+                Err(error) => Err(error),
+            };
+
+            if let Err(error) = result {
+                self.failed = true;
+                return Some(Err(error));
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// `count` whitespace separated numbers, generated as they are read
+    struct Numbers {
+        next: u64,
+        count: u64,
+        pending: Vec<u8>,
+    }
+
+    impl Read for Numbers {
+        fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+            while self.pending.len() < buf.len() && self.next < self.count {
+                self.pending
+                    .extend(format!("{}\n", self.next % 1000).into_bytes());
+                self.next += 1;
+            }
+            let len = buf.len().min(self.pending.len());
+            buf[..len].copy_from_slice(&self.pending[..len]);
+            self.pending.drain(..len);
+            Ok(len)
+        }
+    }
+
+    fn numbers(input: &str) -> ParseResult<'_, u32> {
+        left(number(), space_0()).parse(input)
+    }
+
+    fn words(input: &str) -> ParseResult<'_, String> {
+        map(left(word_str(), space_0()), String::from).parse(input)
+    }
+
+    #[test]
+    fn partial_parsing() {
+        assert_eq!(Partial::Incomplete, partial(&number(), "12", false));
+        assert_eq!(Partial::Done("", 12), partial(&number(), "12", true));
+        assert_eq!(Partial::Done(" 3", 12), partial(&number(), "12 3", false));
+        assert_eq!(
+            Partial::Failed(ParseError::Mismatch("ab")),
+            partial(&match_literal("abc"), "ab", false)
+        );
+        assert_eq!(
+            Partial::Failed(ParseError::Mismatch("x")),
+            partial(&number(), "x", false)
+        );
+    }
+
+    #[test]
+    fn records_across_chunks() {
+        for chunk_size in 1..5 {
+            let values = records(&b"12 345\n6\n\n78"[..], numbers)
+                .chunk_size(chunk_size)
+                .collect::<Result<Vec<_>, _>>()
+                .unwrap();
+            assert_eq!(vec![12, 345, 6, 78], values);
+        }
+
+        let bags = records(&b"1 bag, 22 bags."[..], |input: &str| {
+            left(
+                number(),
+                pair(
+                    either(match_literal(" bags"), match_literal(" bag")),
+                    either(match_literal(", "), match_literal(".")),
+                ),
+            )
+            .parse(input)
+        })
+        .chunk_size(3)
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+        assert_eq!(vec![1, 22], bags);
+
+        let values = records("héllo wörld".as_bytes(), words)
+            .chunk_size(1)
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+        assert_eq!(vec!["héllo", "wörld"], values);
+    }
+
+    #[test]
+    fn stream_errors() {
+        let mut stream = records(&b"1 2 x3\n4"[..], numbers).chunk_size(2);
+        assert_eq!(Some(1), stream.next().and_then(Result::ok));
+        assert_eq!(Some(2), stream.next().and_then(Result::ok));
+        let error = stream.next().unwrap().unwrap_err();
+        assert_eq!("byte 4: unexpected input \"x3\"", error.to_string());
+        assert!(stream.next().is_none());
+
+        let error = records(&b"1 \xff"[..], numbers)
+            .find_map(Result::err)
+            .unwrap();
+        assert_eq!("byte 2: invalid UTF-8", error.to_string());
+
+        let error = records(&b"1 99999999999"[..], numbers)
+            .find_map(Result::err)
+            .unwrap();
+        assert_eq!(
+            "byte 2: number out of range \"99999999999\"",
+            error.to_string()
+        );
+
+        let values = records("ab é".as_bytes(), words).chunk_size(4);
+        assert_eq!(2, values.filter_map(Result::ok).count());
+        let error = records(&"ab é".as_bytes()[..4], words)
+            .chunk_size(4)
+            .find_map(Result::err)
+            .unwrap();
+        assert_eq!("byte 3: invalid UTF-8", error.to_string());
+    }
+
+    #[test]
+    fn records_before_invalid_utf8_are_kept() {
+        for chunk_size in [1, 3, DEFAULT_CHUNK_SIZE] {
+            let results = records(&b"1 2 3 \xff 4"[..], numbers)
+                .chunk_size(chunk_size)
+                .map(|result| result.map_err(|error| error.to_string()))
+                .collect::<Vec<_>>();
+            assert_eq!(
+                vec![Ok(1), Ok(2), Ok(3), Err("byte 6: invalid UTF-8".to_owned())],
+                results
+            );
+        }
+
+        let results = records(&b"12\xff"[..], numbers)
+            .map(|result| result.map_err(|error| error.to_string()))
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![Ok(12), Err("byte 2: invalid UTF-8".to_owned())],
+            results
+        );
+    }
+
+    #[test]
+    fn parsers_have_to_make_progress() {
+        let mut stream = records(&b"12 x"[..], |input: &str| {
+            map(zero_or_more(left(number(), space_0())), |numbers| {
+                numbers.len()
+            })
+            .parse(input)
+        });
+        assert_eq!(Some(1), stream.next().and_then(Result::ok));
+        let error = stream.next().unwrap().unwrap_err();
+        assert_eq!("byte 3: unexpected input \"x\"", error.to_string());
+        assert!(stream.next().is_none());
+    }
+
+    #[test]
+    fn large_generated_input() {
+        let count = 200_000;
+        let generator = Numbers {
+            next: 0,
+            count,
+            pending: Vec::new(),
+        };
+        let mut stream = records(generator, numbers).chunk_size(64);
+        let mut sum = 0u64;
+        let mut max_buffered = 0;
+        while let Some(value) = stream.next() {
+            sum += value.unwrap() as u64;
+            max_buffered = max_buffered.max(stream.buffered());
+        }
+        assert_eq!((0..count).map(|i| i % 1000).sum::<u64>(), sum);
+        assert!(max_buffered <= 2 * 64, "buffered {} bytes", max_buffered);
+    }
+}
@@ -0,0 +1,184 @@
+///
+/// opt-in logging of named parsers, rendered as an indented tree:
+///
+/// ```text
+/// color @ "pale turquoise bags"
+///   word @ "pale turquoise bags"
+///   word ok "pale"
+/// color ok "pale turquoise "
+/// ```
+///
+/// tracing is enabled per thread, so parallel tests don't mix their logs
+///
+use super::*;
+use std::cell::RefCell;
+use std::fmt::Write;
+
+const PREVIEW_LEN: usize = 24;
+
+#[derive(Default)]
+struct Tracer {
+    depth: usize,
+    log: String,
+}
+
+thread_local! {
+    static TRACER: RefCell<Option<Tracer>> = const { RefCell::new(None) };
+}
+
+#[allow(dead_code)]
+pub fn set_trace_enabled(enabled: bool) {
+    TRACER.with(|tracer| {
+        let mut tracer = tracer.borrow_mut();
+        match (enabled, tracer.is_some()) {
+            (true, false) => *tracer = Some(Tracer::default()),
+            (false, true) => *tracer = None,
+            _ => {}
+        }
+    });
+}
+
+/// log collected so far, tracing stays enabled
+#[allow(dead_code)]
+pub fn take_trace() -> String {
+    TRACER.with(|tracer| match tracer.borrow_mut().as_mut() {
+        Some(tracer) => std::mem::take(&mut tracer.log),
+        None => String::new(),
+    })
+}
+
+/// runs `f` with tracing enabled and returns its result along with the log
+#[allow(dead_code)]
+pub fn with_trace<R, F: FnOnce() -> R>(f: F) -> (R, String) {
+    set_trace_enabled(true);
+    let result = f();
+    let log = take_trace();
+    set_trace_enabled(false);
+    (result, log)
+}
+
+fn is_enabled() -> bool {
+    TRACER.with(|tracer| tracer.borrow().is_some())
+}
+
+fn log_line(depth_change: isize, line: std::fmt::Arguments) {
+    TRACER.with(|tracer| {
+        if let Some(tracer) = tracer.borrow_mut().as_mut() {
+            if depth_change < 0 {
+                tracer.depth = tracer.depth.saturating_sub(1);
+            }
+            let indent = tracer.depth * 2;
+            let _ = writeln!(tracer.log, "{:indent$}{}", "", line, indent = indent);
+            if depth_change > 0 {
+                tracer.depth += 1;
+            }
+        }
+    });
+}
+
+/// first few items of the input, cut on an item boundary
+fn preview<I: Input>(input: I) -> String {
+    let mut tail = input;
+    for _ in 0..PREVIEW_LEN {
+        match tail.next_item() {
+            Some((_, next)) => tail = next,
+            None => return format!("{:?}", input),
+        }
+    }
+
+    let (head, _) = input.split_at_position(input.input_len() - tail.input_len());
+    format!("{:?}…", head)
+}
+
+/// logs entry, success with the consumed input, or failure with its position
+/// relative to where the parser started, whenever tracing is enabled
+#[allow(dead_code)]
+pub fn trace<'a, I, P, T>(name: &'static str, parser: P) -> impl Parser<'a, T, I>
+where
+    I: Input,
+    P: Parser<'a, T, I>,
+{
+    move |input: I| {
+        if !is_enabled() {
+            return parser.parse(input);
+        }
+
+        log_line(1, format_args!("{} @ {}", name, preview(input)));
+        let result = parser.parse(input);
+        match &result {
+            Ok((tail, _)) => {
+                let (consumed, _) = input.split_at_position(input.input_len() - tail.input_len());
+                log_line(-1, format_args!("{} ok {}", name, preview(consumed)));
+            }
+            Err(error) => {
+                let kind = match error {
+                    ParseError::Mismatch(_) => "mismatch",
+                    ParseError::Overflow(_) => "overflow",
+                };
+                let offset = input.input_len().saturating_sub(error.input().input_len());
+                log_line(
+                    -1,
+                    format_args!(
+                        "{} {} at +{}: {}",
+                        name,
+                        kind,
+                        offset,
+                        preview(error.input())
+                    ),
+                );
+            }
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn disabled_by_default() {
+        let parser = trace("word", word_str());
+        assert_eq!(Ok((" b", "a")), parser.parse("a b"));
+        assert_eq!("", take_trace());
+    }
+
+    #[test]
+    fn renders_indented_tree() {
+        let parser = trace(
+            "pair",
+            pair(
+                trace("word", word_str()),
+                trace("number", right(space_1(), number())),
+            ),
+        );
+
+        let (result, log) = with_trace(|| parser.parse("abc 12 rest"));
+        assert_eq!(Ok((" rest", ("abc", 12))), result);
+        assert_eq!(
+            "\
+pair @ \"abc 12 rest\"
+  word @ \"abc 12 rest\"
+  word ok \"abc\"
+  number @ \" 12 rest\"
+  number ok \" 12\"
+pair ok \"abc 12\"
+",
+            log
+        );
+
+        let (result, log) = with_trace(|| parser.parse("abc 99999999999"));
+        assert_eq!(Err(ParseError::Overflow("99999999999")), result);
+        assert!(log.contains("\n  number overflow at +1: \"99999999999\"\n"));
+        assert!(log.ends_with("pair overflow at +4: \"99999999999\"\n"));
+    }
+
+    #[test]
+    fn previews_are_truncated() {
+        let input = "ü".repeat(PREVIEW_LEN + 1);
+        let (_, log) = with_trace(|| trace("word", word_str()).parse(&input));
+        let expected = format!("{:?}…", "ü".repeat(PREVIEW_LEN));
+        assert!(log.starts_with(&format!("word @ {}\n", expected)));
+    }
+}
@@ -0,0 +1,179 @@
+///
+/// helpers for parsing a whole puzzle input at once instead of splitting it up by hand
+///
+use super::*;
+use std::fmt;
+
+/// parse failure along with the 1-based number of the line it happened on
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LineError<I> {
+    pub line: usize,
+    pub error: ParseError<I>,
+}
+
+impl<'a> LineError<&'a str> {
+    /// line number of the failure position inside of `source`
+    fn locate(source: &'a str, error: ParseError<&'a str>) -> Self {
+        Self {
+            line: Span::of(source, error.input())
+                .expect("parse errors point into the parsed input")
+                .line,
+            error,
+        }
+    }
+}
+
+impl<I: Input> fmt::Display for LineError<I> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.error {
+            ParseError::Mismatch(input) => {
+                write!(f, "line {}: unexpected input {:?}", self.line, input)
+            }
+            ParseError::Overflow(input) => {
+                write!(f, "line {}: number out of range {:?}", self.line, input)
+            }
+        }
+    }
+}
+
+#[allow(dead_code)]
+pub fn eof<'a, I: Input>() -> impl Parser<'a, (), I> {
+    move |input: I| {
+        if input.input_len() == 0 {
+            Ok((input, ()))
+        } else {
+            Err(ParseError::Mismatch(input))
+        }
+    }
+}
+
+/// fails on any input left over after `parser`
+#[allow(dead_code)]
+pub fn all_consuming<'a, I, P, T>(parser: P) -> impl Parser<'a, T, I>
+where
+    I: Input,
+    P: Parser<'a, T, I>,
+{
+    left(parser, eof())
+}
+
+/// every line of the input parsed with `parser` in full, stops on the first malformed one;
+/// blank lines are skipped like between `blocks`, errors still count them
+#[allow(dead_code)]
+pub fn lines_of<'a, P, T>(parser: P) -> impl Fn(&'a str) -> Result<Vec<T>, LineError<&'a str>>
+where
+    P: Parser<'a, T>,
+{
+    let parser = all_consuming(parser);
+    move |input: &'a str| {
+        input
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty())
+            .map(|(i, line)| {
+                parser
+                    .parse(line)
+                    .map(|(_, value)| value)
+                    .map_err(|error| LineError { line: i + 1, error })
+            })
+            .collect()
+    }
+}
+
+/// groups of consecutive non-blank lines, without the trailing line break,
+/// blank lines may end in `\r\n` or hold nothing but whitespace
+#[allow(dead_code)]
+pub fn blocks(input: &str) -> Vec<&str> {
+    let mut blocks = Vec::new();
+    let mut block: Option<(usize, usize)> = None;
+    for line in input.lines() {
+        if line.trim().is_empty() {
+            blocks.extend(block.take().map(|(start, end)| &input[start..end]));
+        } else {
+            let start = line
+                .offset_from(&input)
+                .expect("lines are slices of the input");
+            let end = start + line.len();
+            block = Some((block.map_or(start, |(start, _)| start), end));
+        }
+    }
+    blocks.extend(block.map(|(start, end)| &input[start..end]));
+    blocks
+}
+
+/// every group of lines separated by blank lines parsed with `parser` in full,
+/// stops on the first malformed one
+#[allow(dead_code)]
+pub fn blocks_of<'a, P, T>(parser: P) -> impl Fn(&'a str) -> Result<Vec<T>, LineError<&'a str>>
+where
+    P: Parser<'a, T>,
+{
+    let parser = all_consuming(parser);
+    move |input: &'a str| {
+        blocks(input)
+            .into_iter()
+            .map(|block| {
+                parser
+                    .parse(block)
+                    .map(|(_, value)| value)
+                    .map_err(|error| LineError::locate(input, error))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn eof_parser() {
+        assert_eq!(Ok(("", ())), eof().parse(""));
+        assert_eq!(Err(ParseError::Mismatch("x")), eof().parse("x"));
+        assert_eq!(Ok((&b""[..], ())), eof().parse(&b""[..]));
+    }
+
+    #[test]
+    fn all_consuming_combinator() {
+        let parser = all_consuming(number());
+        assert_eq!(Ok(("", 42)), parser.parse("42"));
+        assert_eq!(Err(ParseError::Mismatch(" bags")), parser.parse("42 bags"));
+    }
+
+    #[test]
+    fn lines_of_parser() {
+        let parser = lines_of(number());
+        assert_eq!(Ok(vec![1, 2, 3]), parser("1\n2\r\n3\n"));
+        assert_eq!(Ok(vec![]), parser(""));
+        assert_eq!(Ok(vec![1, 2]), parser("\n1\n \n\r\n2\n\n"));
+
+        let error = parser("1\n\n2x\n3").unwrap_err();
+        assert_eq!(
+            LineError {
+                line: 3,
+                error: ParseError::Mismatch("x")
+            },
+            error
+        );
+        assert_eq!("line 3: unexpected input \"x\"", error.to_string());
+    }
+
+    #[test]
+    fn blocks_of_parser() {
+        let parser = blocks_of(recognize(sep_by1(word_str(), space_1())));
+        assert_eq!(
+            Ok(vec!["a b\nc", "d", "e\nf"]),
+            parser("a b\nc\n\nd\n  \n\ne\nf\n")
+        );
+        assert_eq!(Ok(vec!["a"]), parser("\n\na"));
+
+        let error = parser("a\n\nb\nc 1\n\nd").unwrap_err();
+        assert_eq!(
+            LineError {
+                line: 4,
+                error: ParseError::Mismatch(" 1")
+            },
+            error
+        );
+    }
+}